@@ -1,122 +1,392 @@
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
-use globset::{Glob, GlobSet, GlobSetBuilder};
+use globset::{Glob, GlobBuilder, GlobMatcher, GlobSet, GlobSetBuilder};
 use jiff::Timestamp;
 use walkdir::WalkDir;
 
-use crate::config::Config;
+use crate::config::{Config, DEFAULT_LINK_SOURCES, EffectiveParseConfig, GroupConfig};
 use crate::error::{Error, Result};
-use crate::extract::extract_page;
-use crate::model::{self, PublicFile, SiteMetadata};
+use crate::extract::{ExtractOptions, extract_page, matching_selectors, normalize_internal_path};
+use crate::model::{self, Edge, Extensions, Node, PublicFile, SiteMetadata};
+use crate::ring::{RING_DIR, membership};
 
 /// Walks the output directory, extracts links from HTML files, and assembles a [`PublicFile`].
+///
+/// `generated_at` is taken from [`default_generated_at`]. See [`build_at`] to set it explicitly.
 pub fn build(config: &Config) -> Result<PublicFile> {
-    let output_dir = Path::new(&config.output.dir);
+    build_at(config, default_generated_at()?)
+}
 
-    let include_set = compile_glob_set(&config.parse.include)?;
-    let exclude_set = config
-        .parse
-        .exclude
-        .as_ref()
-        .map(|patterns| compile_glob_set(patterns))
-        .transpose()?;
+/// The `generated_at` of a build: `SOURCE_DATE_EPOCH` when set, for reproducible
+/// builds, and the current time otherwise.
+pub fn default_generated_at() -> Result<Timestamp> {
+    match std::env::var("SOURCE_DATE_EPOCH") {
+        Ok(epoch) => parse_source_date_epoch(&epoch),
+        Err(_) => Ok(Timestamp::now()),
+    }
+}
 
-    let exclude_selectors: &[String] = config.parse.exclude_selectors.as_deref().unwrap_or(&[]);
+/// Like [`build`], with an explicit `generated_at` timestamp.
+///
+/// Nodes, edges and friends are sorted, so the same site and timestamp always
+/// give the same file.
+pub fn build_at(config: &Config, generated_at: Timestamp) -> Result<PublicFile> {
+    let extractor = PageExtractor::new(config)?;
 
     let mut nodes = Vec::new();
     let mut edges = Vec::new();
+    for (path, normalized) in site_pages(config)? {
+        let (node, page_edges) = extractor.extract(&path, &normalized)?;
+        nodes.push(node);
+        edges.extend(page_edges);
+    }
 
-    for entry in WalkDir::new(output_dir) {
-        let entry = entry?;
-        if !entry.file_type().is_file() {
-            continue;
-        }
+    assemble(config, nodes, edges, generated_at)
+}
 
-        let relative_path = entry
-            .path()
-            .strip_prefix(output_dir)
-            .expect("walkdir entry should be under output_dir");
+/// A build kept in memory between rebuilds, re-extracting only the pages that changed.
+pub struct IncrementalBuild<'a> {
+    extractor: PageExtractor<'a>,
+    stamps: PageStamps,
+    pages: HashMap<PathBuf, (Node, Vec<Edge>)>,
+}
 
-        // Normalize to forward slashes for cross-platform glob matching
-        let normalized = relative_path.to_string_lossy().replace('\\', "/");
+/// The pages of a site with their modification time and size, see [`stamp_pages`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageStamps(BTreeMap<PathBuf, PageStamp>);
 
-        if !include_set.is_match(&normalized) {
-            continue;
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PageStamp {
+    /// Path relative to the output directory, using forward slashes.
+    normalized: String,
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+/// Stamps the pages of a site, telling which ones changed without reading them.
+pub fn stamp_pages(config: &Config) -> Result<PageStamps> {
+    let mut stamps = BTreeMap::new();
+    for (path, normalized) in site_pages(config)? {
+        let metadata = std::fs::metadata(&path).map_err(|e| Error::FileRead(e, path.clone()))?;
+        let stamp = PageStamp {
+            normalized,
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+        };
+        stamps.insert(path, stamp);
+    }
+    Ok(PageStamps(stamps))
+}
+
+/// Pages re-extracted and dropped by [`IncrementalBuild::update`], relative to the output directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageUpdate {
+    pub extracted: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl PageUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.extracted.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl<'a> IncrementalBuild<'a> {
+    /// Starts a build with no pages; the first [`update`](Self::update) extracts them all.
+    pub fn new(config: &'a Config) -> Result<Self> {
+        Ok(IncrementalBuild {
+            extractor: PageExtractor::new(config)?,
+            stamps: PageStamps::default(),
+            pages: HashMap::new(),
+        })
+    }
+
+    /// Re-extracts the pages whose stamp changed, and drops the pages that are gone.
+    pub fn update(&mut self, stamps: PageStamps) -> Result<PageUpdate> {
+        let mut update = PageUpdate::default();
+
+        for (path, stamp) in &stamps.0 {
+            if self.stamps.0.get(path) == Some(stamp) {
+                continue;
+            }
+            let page = self.extractor.extract(path, &stamp.normalized)?;
+            self.pages.insert(path.clone(), page);
+            update.extracted.push(stamp.normalized.clone());
         }
-        if let Some(ref exc) = exclude_set
-            && exc.is_match(&normalized)
-        {
-            continue;
+        for (path, stamp) in &self.stamps.0 {
+            if !stamps.0.contains_key(path) {
+                self.pages.remove(path);
+                update.removed.push(stamp.normalized.clone());
+            }
         }
 
-        let html = std::fs::read_to_string(entry.path())
-            .map_err(|e| Error::FileRead(e, entry.path().to_path_buf()))?;
-        let page_url = file_path_to_url(&normalized);
+        self.stamps = stamps;
+        Ok(update)
+    }
 
-        let (node, page_edges) = extract_page(
-            &html,
-            &page_url,
-            &config.site.base_url,
-            &config.friends,
-            exclude_selectors,
-        )?;
+    /// Assembles the public file of the pages extracted so far.
+    pub fn public_file(&self, generated_at: Timestamp) -> Result<PublicFile> {
+        let nodes = self.pages.values().map(|(node, _)| node.clone()).collect();
+        let edges = self
+            .pages
+            .values()
+            .flat_map(|(_, edges)| edges.iter().cloned())
+            .collect();
+        assemble(self.extractor.config, nodes, edges, generated_at)
+    }
+}
 
-        nodes.push(node);
-        edges.extend(page_edges);
+/// Extracts the pages of a site with the settings of its config.
+struct PageExtractor<'a> {
+    config: &'a Config,
+    parse: EffectiveParseConfig,
+    friend_urls: Vec<String>,
+    group_rules: Vec<GroupRule<'a>>,
+    group_selectors: Vec<String>,
+}
+
+impl<'a> PageExtractor<'a> {
+    fn new(config: &'a Config) -> Result<Self> {
+        let (group_rules, group_selectors) = compile_group_rules(&config.groups)?;
+        Ok(PageExtractor {
+            config,
+            parse: config.parse.effective(),
+            friend_urls: config
+                .friends
+                .iter()
+                .map(|friend| friend.url.clone())
+                .collect(),
+            group_rules,
+            group_selectors,
+        })
+    }
+
+    /// Extracts the node and edges of the page at `path`, `normalized` relative to the output directory.
+    fn extract(&self, path: &Path, normalized: &str) -> Result<(Node, Vec<Edge>)> {
+        let options = ExtractOptions {
+            exclude_selectors: &self.parse.exclude_selectors,
+            edge_types: &self.config.edge_types,
+            contexts: &self.config.contexts,
+            link_sources: self
+                .config
+                .link_sources
+                .as_deref()
+                .unwrap_or(&DEFAULT_LINK_SOURCES),
+            page_extensions: &self.parse.page_extensions,
+            ..ExtractOptions::new(&self.config.site.base_url, &self.friend_urls)
+        };
+
+        let html =
+            std::fs::read_to_string(path).map_err(|e| Error::FileRead(e, path.to_path_buf()))?;
+        let page_url = file_path_to_url(normalized, &self.parse.page_extensions);
+
+        let (mut node, edges) = extract_page(&html, &page_url, &options)?;
+
+        if !self.group_rules.is_empty() {
+            let matched = if self.group_selectors.is_empty() {
+                Vec::new()
+            } else {
+                matching_selectors(&html, &self.group_selectors)?
+            };
+            node.group = resolve_group(&self.group_rules, &page_url, normalized, &matched);
+        }
+
+        Ok((node, edges))
     }
+}
+
+/// Assembles the public file of a site from the nodes and edges of its pages.
+fn assemble(
+    config: &Config,
+    mut nodes: Vec<Node>,
+    mut edges: Vec<Edge>,
+    generated_at: Timestamp,
+) -> Result<PublicFile> {
+    // Walk order depends on the file system, sort for byte-identical output
+    nodes.sort_by(|a, b| a.url.cmp(&b.url));
+    edges.sort_by(|a, b| {
+        (&a.source, &a.target, a.edge_type.as_str(), &a.context).cmp(&(
+            &b.source,
+            &b.target,
+            b.edge_type.as_str(),
+            &b.context,
+        ))
+    });
+    let mut friends = config.friends.clone();
+    friends.sort_by(|a, b| a.url.cmp(&b.url));
+
+    let rings = config
+        .rings
+        .iter()
+        .map(|ring| membership(&ring.load()?, &config.site.base_url))
+        .collect::<Result<Vec<_>>>()?;
 
     Ok(PublicFile {
         version: String::from(model::PROTOCOL_VERSION),
-        generated_at: utc_timestamp(),
+        generated_at: format_timestamp(generated_at),
         base_url: config.site.base_url.clone(),
         site: SiteMetadata {
             title: config.site.title.clone(),
             description: config.site.description.clone(),
             language: config.site.language.clone(),
+            public_key: None,
+            extensions: Extensions::new(),
         },
-        friends: config.friends.clone(),
+        friends,
         nodes,
         edges,
+        rings,
+        shards: Vec::new(),
+        extensions: Extensions::new(),
     })
 }
 
+/// The pages of the output directory matched by the include and exclude globs,
+/// with their path relative to it, using forward slashes.
+///
+/// Ring navigation files written under [`RING_DIR`] are not pages of the site.
+pub(crate) fn site_pages(config: &Config) -> Result<Vec<(PathBuf, String)>> {
+    let output_dir = Path::new(&config.output.dir);
+
+    let parse = config.parse.effective();
+    let include_set = compile_glob_set(&parse.include)?;
+    let exclude_set = compile_glob_set(&parse.exclude)?;
+    let ring_dir = output_dir.join(RING_DIR);
+
+    let mut pages = Vec::new();
+    for entry in WalkDir::new(output_dir)
+        .into_iter()
+        .filter_entry(|entry| entry.path() != ring_dir)
+    {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let relative_path = entry
+            .path()
+            .strip_prefix(output_dir)
+            .expect("walkdir entry should be under output_dir");
+
+        // Normalize to forward slashes for cross-platform glob matching
+        let normalized = relative_path.to_string_lossy().replace('\\', "/");
+
+        if !include_set.is_match(&normalized) {
+            continue;
+        }
+        if exclude_set.is_match(&normalized) {
+            continue;
+        }
+
+        pages.push((entry.into_path(), normalized));
+    }
+
+    Ok(pages)
+}
+
 fn compile_glob_set(patterns: &[String]) -> Result<GlobSet> {
     let mut builder = GlobSetBuilder::new();
     for pattern in patterns {
-        builder.add(Glob::new(pattern)?);
+        builder.add(glob(pattern)?);
     }
     Ok(builder.build()?)
 }
 
+/// A compiled `[[groups]]` rule.
+struct GroupRule<'a> {
+    name: &'a str,
+    url: Option<GlobMatcher>,
+    path: Option<GlobMatcher>,
+    /// Index into the selectors probed once per page.
+    selector: Option<usize>,
+}
+
+/// Compiles group globs, and gathers group selectors so each page is probed in a single pass.
+fn compile_group_rules(groups: &[GroupConfig]) -> Result<(Vec<GroupRule<'_>>, Vec<String>)> {
+    let mut rules = Vec::with_capacity(groups.len());
+    let mut selectors = Vec::new();
+
+    for group in groups {
+        let selector = group.selector.as_ref().map(|selector| {
+            selectors.push(selector.clone());
+            selectors.len() - 1
+        });
+        rules.push(GroupRule {
+            name: &group.name,
+            url: compile_glob(group.url.as_deref())?,
+            path: compile_glob(group.path.as_deref())?,
+            selector,
+        });
+    }
+
+    Ok((rules, selectors))
+}
+
+fn compile_glob(pattern: Option<&str>) -> Result<Option<GlobMatcher>> {
+    pattern
+        .map(|pattern| glob(pattern).map(|glob| glob.compile_matcher()))
+        .transpose()
+}
+
+/// Parses a glob where `*` stays within a path segment, and `**` crosses them.
+fn glob(pattern: &str) -> Result<Glob> {
+    Ok(GlobBuilder::new(pattern).literal_separator(true).build()?)
+}
+
+/// Returns the name of the first group whose conditions all match the page.
+fn resolve_group(
+    rules: &[GroupRule<'_>],
+    page_url: &str,
+    file_path: &str,
+    matched_selectors: &[bool],
+) -> Option<String> {
+    rules
+        .iter()
+        .find(|rule| {
+            rule.url.as_ref().is_none_or(|glob| glob.is_match(page_url))
+                && rule
+                    .path
+                    .as_ref()
+                    .is_none_or(|glob| glob.is_match(file_path))
+                && rule.selector.is_none_or(|index| matched_selectors[index])
+        })
+        .map(|rule| rule.name.to_owned())
+}
+
 /// Converts a file path (relative to the output directory) to a page URL.
 ///
 /// `about/index.html` → `/about/`, `posts/hello.html` → `/posts/hello`,
 /// `index.html` → `/`.
-fn file_path_to_url(path: &str) -> String {
-    let mut url = format!("/{path}");
-
-    if url.ends_with("/index.html") {
-        url.truncate(url.len() - "index.html".len());
-        return url;
-    }
-
-    if url.ends_with(".html") {
-        url.truncate(url.len() - ".html".len());
-    }
+fn file_path_to_url(path: &str, extensions: &[String]) -> String {
+    normalize_internal_path(&format!("/{path}"), extensions)
+}
 
-    url
+/// Formats a timestamp as ISO 8601 in UTC (`YYYY-MM-DDTHH:MM:SSZ`).
+pub(crate) fn format_timestamp(timestamp: Timestamp) -> String {
+    timestamp.strftime("%Y-%m-%dT%H:%M:%SZ").to_string()
 }
 
-/// Returns the current UTC time formatted as ISO 8601 (`YYYY-MM-DDTHH:MM:SSZ`).
-fn utc_timestamp() -> String {
-    Timestamp::now().strftime("%Y-%m-%dT%H:%M:%SZ").to_string()
+/// Parses a [`SOURCE_DATE_EPOCH`](https://reproducible-builds.org/specs/source-date-epoch/) value.
+fn parse_source_date_epoch(epoch: &str) -> Result<Timestamp> {
+    epoch
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .and_then(|seconds| Timestamp::from_second(seconds).ok())
+        .ok_or_else(|| Error::InvalidSourceDateEpoch(epoch.to_owned()))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{Config, OutputConfig, ParseConfig, SiteConfig};
-    use crate::model::EdgeType;
+    use crate::config::{
+        CacheConfig, Config, DEFAULT_PAGE_EXTENSIONS, EdgeTypeConfig, GroupConfig, OutputConfig,
+        ParseConfig, SiteConfig,
+    };
+    use crate::model::{EdgeType, Friend};
+    use crate::preset::Preset;
     use std::fs;
     use std::path::Path;
     use tempfile::TempDir;
@@ -129,15 +399,18 @@ mod tests {
                 description: None,
                 language: None,
             },
-            friends: vec![String::from("https://bob.dev/")],
+            friends: vec![Friend::from("https://bob.dev/")],
             output: OutputConfig {
                 dir: String::from(output_dir),
+                ..OutputConfig::default()
             },
-            parse: ParseConfig {
-                include: vec![String::from("**/*.html")],
-                exclude: None,
-                exclude_selectors: None,
-            },
+            cache: CacheConfig::default(),
+            parse: ParseConfig::default(),
+            groups: vec![],
+            edge_types: vec![],
+            contexts: vec![],
+            rings: vec![],
+            link_sources: None,
         }
     }
 
@@ -176,7 +449,7 @@ mod tests {
         assert_eq!(result.version, crate::model::PROTOCOL_VERSION);
         assert_eq!(result.base_url, "https://alice.dev/");
         assert_eq!(result.site.title, "Alice's Garden");
-        assert_eq!(result.friends, vec!["https://bob.dev/"]);
+        assert_eq!(result.friends, vec![Friend::from("https://bob.dev/")]);
         assert_eq!(result.nodes.len(), 2);
         assert!(
             result
@@ -229,6 +502,77 @@ mod tests {
         assert_eq!(result.nodes[0].title, "Home");
     }
 
+    #[test]
+    fn build_applies_preset() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        write_file(
+            dir,
+            "index.html",
+            r#"<html><head><title>Home</title></head><body>
+                <header><a href="/">Logo</a></header>
+                <main><a href="about.htm">About</a> <a href="/blog/index.htm">Blog</a></main>
+            </body></html>"#,
+        );
+        write_file(
+            dir,
+            "about.htm",
+            "<html><head><title>About</title></head><body></body></html>",
+        );
+        write_file(
+            dir,
+            "blog/index.htm",
+            "<html><head><title>Blog</title></head><body></body></html>",
+        );
+        write_file(
+            dir,
+            "404.html",
+            "<html><head><title>Not Found</title></head><body></body></html>",
+        );
+
+        let mut config = test_config(dir.to_str().unwrap());
+        config.parse.preset = Some(Preset::Jekyll);
+
+        let result = build(&config).unwrap();
+
+        let urls: Vec<&str> = result.nodes.iter().map(|n| n.url.as_str()).collect();
+        assert_eq!(urls, vec!["/", "/about", "/blog/"]);
+        let targets: Vec<&str> = result.edges.iter().map(|e| e.target.as_str()).collect();
+        assert_eq!(targets, vec!["/about", "/blog/"]);
+    }
+
+    #[test]
+    fn build_keeps_nav_links_when_preset_selectors_are_cleared() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        write_file(
+            dir,
+            "index.html",
+            r#"<html><head><title>Home</title></head><body>
+                <nav><a href="/about/">About</a></nav>
+            </body></html>"#,
+        );
+
+        let mut config = test_config(dir.to_str().unwrap());
+        config.parse.preset = Some(Preset::Hugo);
+        config.parse.exclude_selectors = Some(Vec::new());
+
+        let result = build(&config).unwrap();
+
+        let targets: Vec<&str> = result.edges.iter().map(|e| e.target.as_str()).collect();
+        assert_eq!(targets, vec!["/about/"]);
+    }
+
+    #[test]
+    fn file_path_to_url_strips_every_page_extension() {
+        let extensions = [String::from(".html"), String::from(".htm")];
+        assert_eq!(file_path_to_url("about.htm", &extensions), "/about");
+        assert_eq!(file_path_to_url("blog/index.htm", &extensions), "/blog/");
+        assert_eq!(file_path_to_url("feed.xml", &extensions), "/feed.xml");
+    }
+
     #[test]
     fn build_exclude_selectors() {
         let tmp = TempDir::new().unwrap();
@@ -252,19 +596,267 @@ mod tests {
         assert_eq!(result.edges[0].target, "/visible");
     }
 
+    #[test]
+    fn build_custom_edge_types() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        write_file(
+            dir,
+            "index.html",
+            r#"<html><head><title>Home</title></head><body>
+                <a href="/about/">About</a>
+                <ul class="related-posts"><li><a href="/posts/hello">Hello</a></li></ul>
+            </body></html>"#,
+        );
+
+        let mut config = test_config(dir.to_str().unwrap());
+        config.edge_types = vec![EdgeTypeConfig {
+            selector: String::from(".related-posts"),
+            edge_type: String::from("related"),
+        }];
+
+        let result = build(&config).unwrap();
+
+        assert!(
+            result
+                .edges
+                .iter()
+                .any(|e| e.target == "/about/" && e.edge_type == EdgeType::Internal)
+        );
+        assert!(result.edges.iter().any(|e| e.target == "/posts/hello"
+            && e.edge_type == EdgeType::Custom(String::from("related"))));
+    }
+
+    #[test]
+    fn build_assigns_groups() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        write_file(
+            dir,
+            "index.html",
+            "<html><head><title>Home</title></head><body></body></html>",
+        );
+        write_file(
+            dir,
+            "posts/hello.html",
+            "<html><head><title>Hello</title></head><body><article>Hi</article></body></html>",
+        );
+        write_file(
+            dir,
+            "posts/index.html",
+            "<html><head><title>Posts</title></head><body></body></html>",
+        );
+        write_file(
+            dir,
+            "tags/rust.html",
+            "<html><head><title>Rust</title></head><body></body></html>",
+        );
+        write_file(
+            dir,
+            "tags/archive/2024.html",
+            "<html><head><title>2024</title></head><body></body></html>",
+        );
+        write_file(
+            dir,
+            "about.html",
+            "<html><head><title>About</title></head><body></body></html>",
+        );
+
+        let mut config = test_config(dir.to_str().unwrap());
+        config.groups = vec![
+            GroupConfig {
+                name: String::from("home"),
+                url: Some(String::from("/")),
+                path: None,
+                selector: None,
+            },
+            GroupConfig {
+                name: String::from("post"),
+                url: Some(String::from("/posts/**")),
+                path: None,
+                selector: Some(String::from("article")),
+            },
+            GroupConfig {
+                name: String::from("tag"),
+                url: None,
+                path: Some(String::from("tags/*.html")),
+                selector: None,
+            },
+        ];
+
+        let result = build(&config).unwrap();
+        let group_of = |url: &str| {
+            result
+                .nodes
+                .iter()
+                .find(|n| n.url == url)
+                .and_then(|n| n.group.as_deref())
+        };
+
+        assert_eq!(group_of("/"), Some("home"));
+        assert_eq!(group_of("/posts/hello"), Some("post"));
+        assert_eq!(group_of("/posts/"), None, "no <article> on the listing");
+        assert_eq!(group_of("/tags/rust"), Some("tag"));
+        assert_eq!(
+            group_of("/tags/archive/2024"),
+            None,
+            "`*` stays in one segment"
+        );
+        assert_eq!(group_of("/about"), None);
+    }
+
+    #[test]
+    fn build_rejects_invalid_group_glob() {
+        let tmp = TempDir::new().unwrap();
+
+        let mut config = test_config(tmp.path().to_str().unwrap());
+        config.groups = vec![GroupConfig {
+            name: String::from("broken"),
+            url: Some(String::from("/posts/[")),
+            path: None,
+            selector: None,
+        }];
+
+        assert!(matches!(build(&config), Err(Error::GlobPattern(_))));
+    }
+
+    #[test]
+    fn build_at_is_reproducible() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+
+        write_file(
+            dir,
+            "index.html",
+            r#"<html><head><title>Home</title></head><body>
+                <a href="/zebra">Zebra</a>
+                <a href="https://bob.dev/">Bob</a>
+                <a href="/about/">About</a>
+            </body></html>"#,
+        );
+        write_file(
+            dir,
+            "zebra.html",
+            "<html><head><title>Zebra</title></head></html>",
+        );
+        write_file(
+            dir,
+            "about/index.html",
+            "<html><head><title>About</title></head></html>",
+        );
+
+        let mut config = test_config(dir.to_str().unwrap());
+        config.friends = vec![
+            Friend::from("https://carol.dev/"),
+            Friend::from("https://bob.dev/"),
+        ];
+        let timestamp = Timestamp::from_second(1_771_329_600).unwrap();
+
+        let first = build_at(&config, timestamp).unwrap();
+        let second = build_at(&config, timestamp).unwrap();
+
+        assert_eq!(first.to_json().unwrap(), second.to_json().unwrap());
+        assert_eq!(first.generated_at, "2026-02-17T12:00:00Z");
+        let friend_urls: Vec<&str> = first.friends.iter().map(|f| f.url.as_str()).collect();
+        assert_eq!(friend_urls, vec!["https://bob.dev/", "https://carol.dev/"]);
+
+        let node_urls: Vec<&str> = first.nodes.iter().map(|n| n.url.as_str()).collect();
+        assert_eq!(node_urls, vec!["/", "/about/", "/zebra"]);
+
+        let targets: Vec<&str> = first.edges.iter().map(|e| e.target.as_str()).collect();
+        assert_eq!(targets, vec!["/about/", "/zebra", "https://bob.dev/"]);
+    }
+
+    #[test]
+    fn incremental_build_re_extracts_only_changed_pages() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path();
+        write_file(
+            dir,
+            "index.html",
+            r#"<html><head><title>Home</title></head><body><a href="/about">About</a></body></html>"#,
+        );
+        write_file(
+            dir,
+            "about.html",
+            "<html><head><title>About</title></head></html>",
+        );
+        write_file(
+            dir,
+            "old.html",
+            "<html><head><title>Old</title></head></html>",
+        );
+        let config = test_config(dir.to_str().unwrap());
+        let timestamp = Timestamp::from_second(1_771_329_600).unwrap();
+
+        let mut incremental = IncrementalBuild::new(&config).unwrap();
+        let update = incremental.update(stamp_pages(&config).unwrap()).unwrap();
+        assert_eq!(update.extracted.len(), 3);
+        assert!(
+            incremental
+                .update(stamp_pages(&config).unwrap())
+                .unwrap()
+                .is_empty()
+        );
+
+        // A different size tells the change apart even within the mtime granularity
+        write_file(
+            dir,
+            "about.html",
+            "<html><head><title>About me</title></head></html>",
+        );
+        fs::remove_file(dir.join("old.html")).unwrap();
+        let update = incremental.update(stamp_pages(&config).unwrap()).unwrap();
+
+        assert_eq!(
+            update,
+            PageUpdate {
+                extracted: vec![String::from("about.html")],
+                removed: vec![String::from("old.html")],
+            }
+        );
+        assert_eq!(
+            incremental.public_file(timestamp).unwrap(),
+            build_at(&config, timestamp).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_source_date_epoch_accepts_unix_seconds() {
+        let timestamp = parse_source_date_epoch("1771329600\n").unwrap();
+        assert_eq!(format_timestamp(timestamp), "2026-02-17T12:00:00Z");
+    }
+
+    #[test]
+    fn parse_source_date_epoch_rejects_garbage() {
+        let result = parse_source_date_epoch("yesterday");
+        assert!(matches!(result, Err(Error::InvalidSourceDateEpoch(_))));
+    }
+
     #[test]
     fn file_path_to_url_converts_index() {
-        assert_eq!(file_path_to_url("index.html"), "/");
+        assert_eq!(
+            file_path_to_url("index.html", &DEFAULT_PAGE_EXTENSIONS),
+            "/"
+        );
     }
 
     #[test]
     fn file_path_to_url_converts_nested_index() {
-        assert_eq!(file_path_to_url("about/index.html"), "/about/");
+        assert_eq!(
+            file_path_to_url("about/index.html", &DEFAULT_PAGE_EXTENSIONS),
+            "/about/"
+        );
     }
 
     #[test]
     fn file_path_to_url_converts_named_page() {
-        assert_eq!(file_path_to_url("posts/hello.html"), "/posts/hello");
+        assert_eq!(
+            file_path_to_url("posts/hello.html", &DEFAULT_PAGE_EXTENSIONS),
+            "/posts/hello"
+        );
     }
 
     #[test]
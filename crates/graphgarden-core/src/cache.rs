@@ -0,0 +1,548 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use crate::error::{Error, Result};
+use crate::fetch::{
+    AGENT, Get, MAX_BODY_SIZE, public_file_from, read_body, signature_from,
+    verified_public_file_from,
+};
+use crate::model::{Friend, PublicFile};
+
+pub use crate::config::DEFAULT_CACHE_DIR;
+
+/// A local HTTP cache of friend files, following the caching section of the protocol.
+///
+/// Each file is stored with its `ETag` and `Cache-Control: max-age`. While
+/// fresh, it is served without any request. Once stale, it is revalidated with
+/// `If-None-Match`, and a `304 Not Modified` keeps the stored copy. When the
+/// origin cannot be reached or fails with a `5xx`, the last good copy is served
+/// instead, and listed by [`take_stale`](Self::take_stale) so callers can warn
+/// about it. Any other error status, e.g. a `404` once a friend removes their
+/// file, drops the stored copy.
+pub struct FriendCache {
+    dir: PathBuf,
+    stale: Mutex<Vec<StaleCopy>>,
+}
+
+/// A file served from the cache because its origin could not be reached, or failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaleCopy {
+    pub url: String,
+    /// Why the origin could not be reached.
+    pub error: String,
+    /// When the copy was last fetched or revalidated.
+    pub fetched_at: SystemTime,
+}
+
+/// A cached response, stored as one JSON file per URL, see [`FriendCache::entry_path`].
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    url: String,
+    etag: Option<String>,
+    /// Unix time of the last fetch or revalidation, in seconds.
+    fetched_at: u64,
+    /// Seconds the entry stays fresh, `None` to revalidate it every time.
+    max_age: Option<u64>,
+    body: String,
+}
+
+impl Entry {
+    fn is_fresh(&self, now: u64) -> bool {
+        self.max_age
+            .is_some_and(|max_age| now < self.fetched_at.saturating_add(max_age))
+    }
+}
+
+/// What a response allows the cache to do, from its `Cache-Control` and `Age` headers.
+#[derive(Debug, PartialEq, Eq)]
+struct Policy {
+    max_age: Option<u64>,
+    store: bool,
+}
+
+impl Policy {
+    fn from_headers(cache_control: Option<&str>, age: Option<&str>) -> Self {
+        let age = age
+            .and_then(|age| age.trim().parse::<u64>().ok())
+            .unwrap_or(0);
+        let mut policy = Policy {
+            max_age: None,
+            store: true,
+        };
+
+        for directive in cache_control.unwrap_or_default().split(',') {
+            let directive = directive.trim().to_ascii_lowercase();
+            match directive.split_once('=') {
+                Some(("max-age", seconds)) => {
+                    if let Ok(seconds) = seconds.trim_matches('"').parse::<u64>()
+                        && policy.max_age != Some(0)
+                    {
+                        policy.max_age = Some(seconds.saturating_sub(age));
+                    }
+                }
+                _ if directive == "no-cache" => policy.max_age = Some(0),
+                _ if directive == "no-store" => policy.store = false,
+                _ => {}
+            }
+        }
+
+        policy
+    }
+}
+
+/// Why a request failed, which tells whether a stale copy may stand in.
+enum Failure {
+    /// No response, e.g. a timeout, or a `5xx`: the origin may be back soon.
+    Unreachable(String),
+    /// A `4xx`: the origin answered that the file is not there anymore.
+    Refused(String),
+}
+
+/// A response of the origin, before it is stored. `body` is `None` for a `304 Not Modified`.
+struct Response {
+    status: u16,
+    etag: Option<String>,
+    policy: Policy,
+    body: Option<String>,
+}
+
+impl FriendCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        FriendCache {
+            dir: dir.into(),
+            stale: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Like [`fetch::fetch_public_file`](crate::fetch::fetch_public_file), through the cache.
+    pub fn fetch_public_file(&self, input: &str) -> Result<PublicFile> {
+        public_file_from(self, input)
+    }
+
+    /// Like [`fetch::fetch_signature`](crate::fetch::fetch_signature), through the cache.
+    pub fn fetch_signature(&self, input: &str) -> Result<String> {
+        signature_from(self, input)
+    }
+
+    /// Like [`fetch::fetch_verified_public_file`](crate::fetch::fetch_verified_public_file), through the cache.
+    pub fn fetch_verified_public_file(&self, friend: &Friend) -> Result<PublicFile> {
+        verified_public_file_from(self, friend)
+    }
+
+    /// The stale copies served since the last call.
+    pub fn take_stale(&self) -> Vec<StaleCopy> {
+        std::mem::take(&mut self.stale.lock().unwrap_or_else(|error| error.into_inner()))
+    }
+
+    /// Removes the entries not fetched nor revalidated for `unused_for`, returning how many.
+    ///
+    /// [`Duration::ZERO`] empties the cache. Unreadable entries are removed too.
+    pub fn prune(&self, unused_for: Duration) -> Result<usize> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(error) => return Err(Error::FileRead(error, self.dir.clone())),
+        };
+
+        let cutoff = unix_now().saturating_sub(unused_for.as_secs());
+        let mut removed = 0;
+        for entry in entries {
+            let path = entry
+                .map_err(|error| Error::FileRead(error, self.dir.clone()))?
+                .path();
+            if path.extension().is_none_or(|extension| extension != "json") {
+                continue;
+            }
+
+            let keep = unused_for > Duration::ZERO
+                && read_entry(&path).is_some_and(|entry| entry.fetched_at > cutoff);
+            if !keep {
+                fs::remove_file(&path).map_err(|error| Error::FileWrite(error, path.clone()))?;
+                removed += 1;
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Entries are named after the SHA-256 of their URL, which stays within file
+    /// name length limits. The URL itself is stored in the entry.
+    fn entry_path(&self, url: &Url) -> PathBuf {
+        let digest = Sha256::digest(url.as_str().as_bytes());
+        let name: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+        self.dir.join(format!("{name}.json"))
+    }
+
+    fn write_entry(&self, path: &Path, entry: &Entry) -> Result<()> {
+        let json = serde_json::to_string(entry)
+            .map_err(|error| Error::FileWrite(std::io::Error::other(error), path.to_path_buf()))?;
+        fs::create_dir_all(&self.dir).map_err(|error| Error::FileWrite(error, self.dir.clone()))?;
+        fs::write(path, json).map_err(|error| Error::FileWrite(error, path.to_path_buf()))
+    }
+}
+
+impl Get for FriendCache {
+    fn get(&self, url: &Url) -> Result<String> {
+        let now = unix_now();
+        let path = self.entry_path(url);
+        let cached = read_entry(&path).filter(|entry| entry.url == url.as_str());
+        if let Some(entry) = cached.as_ref().filter(|entry| entry.is_fresh(now)) {
+            return Ok(entry.body.clone());
+        }
+
+        let etag = cached.as_ref().and_then(|entry| entry.etag.as_deref());
+        let response = match (request(url, etag), cached) {
+            (Ok(response), cached) => (response, cached),
+            (Err(Failure::Unreachable(error)), Some(entry)) => {
+                self.stale
+                    .lock()
+                    .unwrap_or_else(|error| error.into_inner())
+                    .push(StaleCopy {
+                        url: url.to_string(),
+                        error,
+                        fetched_at: UNIX_EPOCH + Duration::from_secs(entry.fetched_at),
+                    });
+                return Ok(entry.body);
+            }
+            (Err(Failure::Refused(error)), Some(_)) => {
+                let _ = fs::remove_file(&path);
+                return Err(Error::Fetch(url.to_string(), error));
+            }
+            (Err(Failure::Unreachable(error) | Failure::Refused(error)), None) => {
+                return Err(Error::Fetch(url.to_string(), error));
+            }
+        };
+
+        let entry = match response {
+            (
+                Response {
+                    etag,
+                    policy,
+                    body: None,
+                    ..
+                },
+                Some(entry),
+            ) => Entry {
+                etag: etag.or(entry.etag),
+                fetched_at: now,
+                max_age: policy.max_age,
+                ..entry
+            },
+            (
+                Response {
+                    etag,
+                    policy,
+                    body: Some(body),
+                    ..
+                },
+                _,
+            ) => {
+                if !policy.store {
+                    // Stale copies must not outlive a `no-store`
+                    let _ = fs::remove_file(&path);
+                    return Ok(body);
+                }
+                Entry {
+                    url: url.to_string(),
+                    etag,
+                    fetched_at: now,
+                    max_age: policy.max_age,
+                    body,
+                }
+            }
+            (Response { status, .. }, None) => {
+                return Err(Error::Fetch(
+                    url.to_string(),
+                    format!("unexpected status {status} without a cached copy"),
+                ));
+            }
+        };
+
+        self.write_entry(&path, &entry)?;
+        Ok(entry.body)
+    }
+}
+
+/// Requests `url`, conditionally when an `etag` is known.
+fn request(url: &Url, etag: Option<&str>) -> std::result::Result<Response, Failure> {
+    let mut request = AGENT.get(url.as_str());
+    if let Some(etag) = etag {
+        request = request.header("If-None-Match", etag);
+    }
+    let mut response = request.call().map_err(|error| match error {
+        ureq::Error::StatusCode(status) if status < 500 => Failure::Refused(error.to_string()),
+        error => Failure::Unreachable(error.to_string()),
+    })?;
+
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from)
+    };
+    let status = response.status().as_u16();
+    let etag = header("ETag");
+    let policy = Policy::from_headers(header("Cache-Control").as_deref(), header("Age").as_deref());
+    let body = match status {
+        304 => None,
+        _ => Some(read_body(response.body_mut(), MAX_BODY_SIZE).map_err(Failure::Unreachable)?),
+    };
+
+    Ok(Response {
+        status,
+        etag,
+        policy,
+        body,
+    })
+}
+
+fn read_entry(path: &Path) -> Option<Entry> {
+    let json = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::thread::{self, JoinHandle};
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    type Seen = Arc<Mutex<Vec<Option<String>>>>;
+    /// Status, headers and body of a response.
+    type Reply = (u16, Vec<(&'static str, &'static str)>, &'static str);
+
+    /// Serves `responses` in order, recording the `If-None-Match` of each request.
+    ///
+    /// Each connection is closed after its response, and the origin stops
+    /// listening once every response is served.
+    fn origin(responses: Vec<Reply>) -> (Url, Seen, JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!(
+            "http://{}/.well-known/graphgarden.json",
+            listener.local_addr().unwrap()
+        ))
+        .unwrap();
+        let seen = Seen::default();
+
+        let recorded = Arc::clone(&seen);
+        let handle = thread::spawn(move || {
+            for (status, headers, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(&stream);
+                let mut if_none_match = None;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':')
+                        && name.eq_ignore_ascii_case("If-None-Match")
+                    {
+                        if_none_match = Some(value.trim().to_owned());
+                    }
+                }
+                recorded.lock().unwrap().push(if_none_match);
+
+                let mut response = format!("HTTP/1.1 {status} Status\r\n");
+                for (name, value) in headers {
+                    response.push_str(&format!("{name}: {value}\r\n"));
+                }
+                response.push_str(&format!(
+                    "Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                ));
+                (&stream).write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        (url, seen, handle)
+    }
+
+    #[test]
+    fn serves_fresh_copies_without_requests() {
+        let tmp = TempDir::new().unwrap();
+        let cache = FriendCache::new(tmp.path());
+        let (url, seen, handle) = origin(vec![(
+            200,
+            vec![
+                ("ETag", "\"v1\""),
+                ("Cache-Control", "public, max-age=3600"),
+            ],
+            "first",
+        )]);
+
+        assert_eq!(cache.get(&url).unwrap(), "first");
+        handle.join().unwrap();
+        assert_eq!(cache.get(&url).unwrap(), "first");
+        assert_eq!(seen.lock().unwrap().len(), 1);
+        assert!(cache.take_stale().is_empty());
+    }
+
+    #[test]
+    fn revalidates_stale_copies_with_their_etag() {
+        let tmp = TempDir::new().unwrap();
+        let cache = FriendCache::new(tmp.path());
+        let (url, seen, handle) = origin(vec![
+            (
+                200,
+                vec![("ETag", "\"v1\""), ("Cache-Control", "no-cache")],
+                "first",
+            ),
+            (304, vec![("Cache-Control", "max-age=60")], ""),
+            (200, vec![("ETag", "\"v2\"")], "second"),
+        ]);
+
+        assert_eq!(cache.get(&url).unwrap(), "first");
+        assert_eq!(cache.get(&url).unwrap(), "first");
+        // Fresh for 60 seconds after the revalidation
+        assert_eq!(cache.get(&url).unwrap(), "first");
+
+        cache.prune(Duration::ZERO).unwrap();
+        assert_eq!(cache.get(&url).unwrap(), "second");
+        handle.join().unwrap();
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [None, Some(String::from("\"v1\"")), None]
+        );
+    }
+
+    #[test]
+    fn falls_back_to_the_last_good_copy() {
+        let tmp = TempDir::new().unwrap();
+        let cache = FriendCache::new(tmp.path());
+        let (url, _, handle) = origin(vec![(200, vec![("Cache-Control", "max-age=0")], "good")]);
+
+        assert_eq!(cache.get(&url).unwrap(), "good");
+        handle.join().unwrap();
+
+        assert_eq!(cache.get(&url).unwrap(), "good");
+        let stale = cache.take_stale();
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].url, url.as_str());
+        assert!(cache.take_stale().is_empty());
+    }
+
+    #[test]
+    fn falls_back_on_server_errors_only() {
+        let tmp = TempDir::new().unwrap();
+        let cache = FriendCache::new(tmp.path());
+        let (url, _, handle) = origin(vec![
+            (200, vec![("Cache-Control", "max-age=0")], "good"),
+            (503, vec![], "down"),
+            (404, vec![], "gone"),
+            (200, vec![], "back"),
+        ]);
+
+        assert_eq!(cache.get(&url).unwrap(), "good");
+        assert_eq!(cache.get(&url).unwrap(), "good");
+        assert_eq!(cache.take_stale().len(), 1);
+
+        assert!(matches!(cache.get(&url), Err(Error::Fetch(_, _))));
+        assert!(cache.take_stale().is_empty());
+        assert!(!cache.entry_path(&url).exists(), "a 404 drops the copy");
+
+        assert_eq!(cache.get(&url).unwrap(), "back");
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn does_not_store_no_store_responses() {
+        let tmp = TempDir::new().unwrap();
+        let cache = FriendCache::new(tmp.path());
+        let (url, _, handle) = origin(vec![(200, vec![("Cache-Control", "no-store")], "secret")]);
+
+        assert_eq!(cache.get(&url).unwrap(), "secret");
+        handle.join().unwrap();
+
+        assert!(matches!(cache.get(&url), Err(Error::Fetch(_, _))));
+    }
+
+    #[test]
+    fn prunes_entries_unused_for_a_while() {
+        let tmp = TempDir::new().unwrap();
+        let cache = FriendCache::new(tmp.path().join("cache"));
+        assert_eq!(cache.prune(Duration::ZERO).unwrap(), 0);
+
+        let url = Url::parse("https://bob.dev/.well-known/graphgarden.json").unwrap();
+        let path = cache.entry_path(&url);
+        let entry = |fetched_at| Entry {
+            url: url.to_string(),
+            etag: None,
+            fetched_at,
+            max_age: None,
+            body: String::new(),
+        };
+
+        cache.write_entry(&path, &entry(unix_now())).unwrap();
+        assert_eq!(cache.prune(Duration::from_secs(3600)).unwrap(), 0);
+
+        cache.write_entry(&path, &entry(unix_now() - 7200)).unwrap();
+        assert_eq!(cache.prune(Duration::from_secs(3600)).unwrap(), 1);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn names_entries_after_a_hash_of_their_url() {
+        let tmp = TempDir::new().unwrap();
+        let cache = FriendCache::new(tmp.path());
+        let long = Url::parse(&format!("https://bob.dev/{}.json", "shard-".repeat(100))).unwrap();
+
+        let name = cache.entry_path(&long);
+        let name = name.file_name().unwrap().to_str().unwrap();
+        assert_eq!(name.len(), 64 + ".json".len());
+        assert!(
+            name.trim_end_matches(".json")
+                .bytes()
+                .all(|byte| byte.is_ascii_hexdigit())
+        );
+
+        let other = Url::parse("https://bob.dev/.well-known/graphgarden.json").unwrap();
+        assert_ne!(cache.entry_path(&long), cache.entry_path(&other));
+    }
+
+    #[test]
+    fn reads_the_cache_control_policy() {
+        let policy = Policy::from_headers(Some("public, max-age=3600"), Some("600"));
+        assert_eq!(
+            policy,
+            Policy {
+                max_age: Some(3000),
+                store: true
+            }
+        );
+
+        let policy = Policy::from_headers(Some("max-age=3600, no-cache"), None);
+        assert_eq!(policy.max_age, Some(0));
+
+        let policy = Policy::from_headers(Some("No-Store"), None);
+        assert!(!policy.store);
+
+        assert_eq!(Policy::from_headers(None, None).max_age, None);
+    }
+}
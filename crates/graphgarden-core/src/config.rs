@@ -1,20 +1,39 @@
 use std::path::Path;
 use std::str::FromStr;
+use std::sync::LazyLock;
 
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer, Serialize};
 use url::Url;
 
 use crate::error::{Error, Result};
+use crate::model::{Encoding, Friend, Ring, RingMember, ShardBy};
+use crate::output::{MAX_BROTLI_LEVEL, MAX_GZIP_LEVEL};
+use crate::preset::Preset;
+use crate::ring::load_ring;
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 pub struct Config {
     pub site: SiteConfig,
+    /// Friend sites, as bare URLs or as tables with details.
     #[serde(default)]
-    pub friends: Vec<String>,
+    pub friends: Vec<Friend>,
     #[serde(default)]
     pub output: OutputConfig,
     #[serde(default)]
+    pub cache: CacheConfig,
+    #[serde(default)]
     pub parse: ParseConfig,
+    #[serde(default)]
+    pub groups: Vec<GroupConfig>,
+    #[serde(default)]
+    pub edge_types: Vec<EdgeTypeConfig>,
+    #[serde(default)]
+    pub contexts: Vec<ContextConfig>,
+    /// Webrings the site belongs to, declared in the public file.
+    #[serde(default)]
+    pub rings: Vec<RingConfig>,
+    /// Replaces the [`DEFAULT_LINK_SOURCES`] when set.
+    pub link_sources: Option<Vec<LinkSourceConfig>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
@@ -29,34 +48,262 @@ pub struct SiteConfig {
 #[serde(default)]
 pub struct OutputConfig {
     pub dir: String,
+    /// Form of the written public file, `expanded` (default) or `compact`.
+    pub encoding: Encoding,
+    /// Also writes `graphgarden.json.gz` at this gzip level (0–9).
+    pub gzip: Option<u32>,
+    /// Also writes `graphgarden.json.br` at this brotli level (0–11).
+    pub brotli: Option<u32>,
+    /// Splits nodes and edges into shard files, by `"prefix"` or by a number of nodes.
+    #[serde(deserialize_with = "deserialize_shard")]
+    pub shard: Option<ShardBy>,
+    /// Publishes `graphgarden-changes.json` with this many generations of changes.
+    pub changes: Option<usize>,
+}
+
+/// Raw form of [`OutputConfig::shard`]: `shard = "prefix"` or `shard = 5000`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawShard {
+    Strategy(ShardStrategy),
+    Size(usize),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ShardStrategy {
+    Prefix,
+}
+
+fn deserialize_shard<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<ShardBy>, D::Error> {
+    Ok(
+        Option::<RawShard>::deserialize(deserializer)?.map(|raw| match raw {
+            RawShard::Strategy(ShardStrategy::Prefix) => ShardBy::Prefix,
+            RawShard::Size(size) => ShardBy::Size(size),
+        }),
+    )
 }
 
 impl Default for OutputConfig {
     fn default() -> Self {
         Self {
             dir: String::from("./dist"),
+            encoding: Encoding::default(),
+            gzip: None,
+            brotli: None,
+            shard: None,
+            changes: None,
         }
     }
 }
 
+/// Local cache of the friend files fetched by the CLI.
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(default)]
+pub struct CacheConfig {
+    /// Directory of the cache, relative to the working directory.
+    pub dir: String,
+}
+
+/// Cache directory used when the config sets none, relative to the working directory.
+pub const DEFAULT_CACHE_DIR: &str = ".graphgarden-cache";
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            dir: String::from(DEFAULT_CACHE_DIR),
+        }
+    }
+}
+
+/// A webring the site belongs to, from a manifest or declared inline.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+#[serde(default)]
+pub struct RingConfig {
+    /// Path to the ring manifest, e.g. `ring.toml`.
+    pub manifest: Option<String>,
+    /// Name of a ring declared inline, instead of a manifest.
+    pub name: Option<String>,
+    /// Home page of a ring declared inline.
+    pub url: Option<String>,
+    /// Members of a ring declared inline, in ring order.
+    pub members: Vec<RingMember>,
+    /// Write a navigation bar, its JSON and a random member page, see [`crate::ring::write_ring_navigation`].
+    pub nav: bool,
+}
+
+impl RingConfig {
+    /// Loads the manifest, or assembles the ring declared inline, failing
+    /// when the ring breaks a rule of [`Ring::validate`].
+    pub fn load(&self) -> Result<Ring> {
+        let ring = match &self.manifest {
+            Some(manifest) => load_ring(manifest)?,
+            None => Ring {
+                name: self.name.clone().unwrap_or_default(),
+                url: self.url.clone(),
+                description: None,
+                moderators: Vec::new(),
+                members: self.members.clone(),
+            },
+        };
+
+        let violations = ring.validate();
+        if !violations.is_empty() {
+            let violations: Vec<String> = violations.iter().map(ToString::to_string).collect();
+            return Err(Error::InvalidRing(ring.name, violations.join("; ")));
+        }
+        Ok(ring)
+    }
+}
+
+/// The `[parse]` section, as written. See [`ParseConfig::effective`] for the
+/// settings once the preset is applied.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+#[serde(default)]
 pub struct ParseConfig {
-    pub include: Vec<String>,
+    /// Generator whose output conventions give the defaults of this section.
+    pub preset: Option<Preset>,
+    /// Replaces the globs of the preset, or `**/*.html`, when set.
+    pub include: Option<Vec<String>>,
+    /// Extends the globs of the preset.
     pub exclude: Option<Vec<String>>,
+    /// Replaces the selectors of the preset when set.
     pub exclude_selectors: Option<Vec<String>>,
+    /// Replaces the extensions of the preset, or `.html`, when set.
+    pub page_extensions: Option<Vec<String>>,
 }
 
-impl Default for ParseConfig {
-    fn default() -> Self {
+/// The `[parse]` settings in effect, once the preset is expanded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EffectiveParseConfig {
+    /// Globs of the pages to parse, relative to the output directory.
+    pub include: Vec<String>,
+    /// Globs of the pages to skip.
+    pub exclude: Vec<String>,
+    /// Links inside elements matching these CSS selectors are ignored.
+    pub exclude_selectors: Vec<String>,
+    /// Extensions stripped from page URLs and internal links, e.g. `/about.html` → `/about`.
+    pub page_extensions: Vec<String>,
+}
+
+impl EffectiveParseConfig {
+    /// Renders the settings as a `[parse]` section.
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(format!("[parse]\n{}", toml::to_string(self)?))
+    }
+}
+
+impl ParseConfig {
+    /// Expands the preset: `include`, `exclude_selectors` and `page_extensions`
+    /// replace the preset's values when set, while `exclude` extends them.
+    pub fn effective(&self) -> EffectiveParseConfig {
+        let owned = |values: &[&str]| values.iter().map(|value| String::from(*value)).collect();
+        let preset = |values: fn(Preset) -> &'static [&'static str]| {
+            self.preset.map(values).map(owned).unwrap_or_default()
+        };
+        let extend = |mut base: Vec<String>, extra: &Option<Vec<String>>| {
+            base.extend(extra.iter().flatten().cloned());
+            base
+        };
+
+        EffectiveParseConfig {
+            include: self.include.clone().unwrap_or_else(|| match self.preset {
+                Some(preset) => owned(preset.include()),
+                None => vec![String::from("**/*.html")],
+            }),
+            exclude: extend(preset(Preset::exclude), &self.exclude),
+            exclude_selectors: self
+                .exclude_selectors
+                .clone()
+                .unwrap_or_else(|| preset(Preset::exclude_selectors)),
+            page_extensions: self
+                .page_extensions
+                .clone()
+                .unwrap_or_else(|| match self.preset {
+                    Some(preset) => owned(preset.page_extensions()),
+                    None => DEFAULT_PAGE_EXTENSIONS.clone(),
+                }),
+        }
+    }
+}
+
+/// A `[[groups]]` rule assigning a group name to matching nodes.
+///
+/// Every condition that is set must match; the first matching rule wins.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct GroupConfig {
+    pub name: String,
+    /// Glob over the page URL, e.g. `/posts/**`.
+    pub url: Option<String>,
+    /// Glob over the file path relative to `output.dir`, e.g. `tags/**/*.html`.
+    pub path: Option<String>,
+    /// CSS selector that must match at least one element on the page, e.g. `article`.
+    pub selector: Option<String>,
+}
+
+/// An `[[edge_types]]` rule giving a custom `type` to internal links inside matching elements.
+///
+/// When rules are nested, the innermost matching element wins. Friend links
+/// keep the `friend` type.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct EdgeTypeConfig {
+    /// CSS selector for the element containing the links (or for the links themselves).
+    pub selector: String,
+    #[serde(rename = "type")]
+    pub edge_type: String,
+}
+
+/// A `[[contexts]]` rule labelling the `context` of links inside matching elements.
+///
+/// Applies on top of the built-in landmarks (`nav`, `header`, `main`, `aside`,
+/// `footer`); the innermost matching element wins.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ContextConfig {
+    pub selector: String,
+    pub label: String,
+}
+
+/// A `[[link_sources]]` entry: links are read from `attribute` on elements matching `selector`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct LinkSourceConfig {
+    pub selector: String,
+    pub attribute: String,
+    /// Custom edge type for these links, instead of `internal`. Friend links stay `friend`.
+    #[serde(rename = "type")]
+    pub edge_type: Option<String>,
+    /// Context label for these links, instead of their enclosing landmark.
+    pub context: Option<String>,
+}
+
+impl LinkSourceConfig {
+    fn new(selector: &str, attribute: &str, context: Option<&str>) -> Self {
         Self {
-            include: vec![String::from("**/*.html")],
-            exclude: None,
-            exclude_selectors: None,
+            selector: selector.to_owned(),
+            attribute: attribute.to_owned(),
+            edge_type: None,
+            context: context.map(str::to_owned),
         }
     }
 }
 
+/// Extensions stripped from page URLs and internal links, without a preset or `page_extensions`.
+pub static DEFAULT_PAGE_EXTENSIONS: LazyLock<Vec<String>> =
+    LazyLock::new(|| vec![String::from(".html")]);
+
+/// Link sources used when `[[link_sources]]` isn't set: anchors, image map areas,
+/// SVG anchors, and `rel=next`/`rel=prev` pagination links.
+pub static DEFAULT_LINK_SOURCES: LazyLock<Vec<LinkSourceConfig>> = LazyLock::new(|| {
+    vec![
+        LinkSourceConfig::new("a[href]", "href", None),
+        LinkSourceConfig::new("area[href]", "href", None),
+        LinkSourceConfig::new(r"a[xlink\:href]", "xlink:href", None),
+        LinkSourceConfig::new("link[href][rel~=next]", "href", Some("pagination")),
+        LinkSourceConfig::new("link[href][rel~=prev]", "href", Some("pagination")),
+    ]
+});
+
 impl Config {
     pub fn from_file(path: impl AsRef<Path>) -> Result<Config> {
         let path = path.as_ref();
@@ -69,7 +316,10 @@ impl Config {
 
     /// Validates the parsed config: checks that `base_url` is a well-formed
     /// HTTP(S) URL with a trailing slash, that `output.dir` exists as a
-    /// directory, and that every friend URL is a valid HTTP(S) URL.
+    /// directory, that every friend URL is a valid HTTP(S) URL, that
+    /// every group has a name and at least one condition, and that custom
+    /// edge types (of edge type rules and link sources) don't shadow the
+    /// built-in ones.
     pub fn validate(&self) -> Result<()> {
         validate_base_url(&self.site.base_url)?;
 
@@ -77,9 +327,38 @@ impl Config {
         if !output_dir.is_dir() {
             return Err(Error::OutputDirNotFound(output_dir.to_path_buf()));
         }
+        validate_compression_level("gzip", self.output.gzip, MAX_GZIP_LEVEL)?;
+        validate_compression_level("brotli", self.output.brotli, MAX_BROTLI_LEVEL)?;
+        if self.output.shard == Some(ShardBy::Size(0)) {
+            return Err(Error::InvalidShardSize);
+        }
+        if self.output.changes == Some(0) {
+            return Err(Error::InvalidChangeGenerations);
+        }
 
         for friend in &self.friends {
-            validate_friend_url(friend)?;
+            validate_friend_url(&friend.url)?;
+            if let Some(public_file) = &friend.public_file {
+                validate_friend_url(public_file)?;
+            }
+        }
+
+        for group in &self.groups {
+            validate_group(group)?;
+        }
+
+        for rule in &self.edge_types {
+            validate_edge_type(&rule.edge_type)?;
+        }
+
+        for ring in &self.rings {
+            validate_ring(ring)?;
+        }
+
+        for source in self.link_sources.iter().flatten() {
+            if let Some(edge_type) = &source.edge_type {
+                validate_edge_type(edge_type)?;
+            }
         }
 
         Ok(())
@@ -96,7 +375,7 @@ fn validate_http_url(raw: &str) -> std::result::Result<Url, String> {
 }
 
 /// Validates `base_url`: must be a well-formed HTTP(S) URL ending with `/`.
-fn validate_base_url(raw: &str) -> Result<()> {
+pub(crate) fn validate_base_url(raw: &str) -> Result<()> {
     validate_http_url(raw).map_err(|reason| Error::InvalidBaseUrl(raw.to_owned(), reason))?;
 
     if !raw.ends_with('/') {
@@ -115,6 +394,74 @@ fn validate_friend_url(raw: &str) -> Result<()> {
     Ok(())
 }
 
+/// Validates an optional compression level against its format's maximum.
+fn validate_compression_level(format: &'static str, level: Option<u32>, max: u32) -> Result<()> {
+    match level {
+        Some(level) if level > max => Err(Error::InvalidCompressionLevel(format, level, max)),
+        _ => Ok(()),
+    }
+}
+
+/// Validates a group rule: needs a non-empty name and at least one condition.
+fn validate_group(group: &GroupConfig) -> Result<()> {
+    if group.name.trim().is_empty() {
+        return Err(Error::InvalidGroup(
+            group.name.clone(),
+            String::from("name must not be empty"),
+        ));
+    }
+
+    if group.url.is_none() && group.path.is_none() && group.selector.is_none() {
+        return Err(Error::InvalidGroup(
+            group.name.clone(),
+            String::from("must set at least one of `url`, `path` or `selector`"),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validates that a ring has either a manifest, or a name and members.
+fn validate_ring(ring: &RingConfig) -> Result<()> {
+    let label = ring
+        .manifest
+        .as_deref()
+        .or(ring.name.as_deref())
+        .unwrap_or_default();
+
+    match (&ring.manifest, &ring.name) {
+        (Some(_), None) if ring.members.is_empty() && ring.url.is_none() => Ok(()),
+        (Some(_), _) => Err(Error::InvalidRing(
+            label.to_owned(),
+            String::from("set either `manifest`, or `name` and `members`"),
+        )),
+        (None, Some(name)) if !name.trim().is_empty() && !ring.members.is_empty() => Ok(()),
+        (None, _) => Err(Error::InvalidRing(
+            label.to_owned(),
+            String::from("must set a `manifest`, or a `name` and `members`"),
+        )),
+    }
+}
+
+/// Validates a custom edge type name: non-empty and not `internal` or `friend`.
+fn validate_edge_type(name: &str) -> Result<()> {
+    if name.trim().is_empty() {
+        return Err(Error::InvalidEdgeType(
+            name.to_owned(),
+            String::from("must not be empty"),
+        ));
+    }
+
+    if name == "internal" || name == "friend" {
+        return Err(Error::InvalidEdgeType(
+            name.to_owned(),
+            String::from("is a built-in edge type"),
+        ));
+    }
+
+    Ok(())
+}
+
 impl FromStr for Config {
     type Err = Error;
 
@@ -127,7 +474,10 @@ impl FromStr for Config {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{OutputConfig, ParseConfig, SiteConfig};
+    use crate::config::{
+        ContextConfig, EdgeTypeConfig, GroupConfig, LinkSourceConfig, OutputConfig, ParseConfig,
+        RingConfig, SiteConfig,
+    };
 
     /// Helper to build a config with the given base_url, output dir, and friends.
     fn test_config(base_url: &str, output_dir: &str, friends: Vec<String>) -> Config {
@@ -138,11 +488,18 @@ mod tests {
                 description: None,
                 language: None,
             },
-            friends,
+            friends: friends.into_iter().map(Friend::from).collect(),
             output: OutputConfig {
                 dir: String::from(output_dir),
+                ..OutputConfig::default()
             },
+            cache: CacheConfig::default(),
             parse: ParseConfig::default(),
+            groups: vec![],
+            edge_types: vec![],
+            contexts: vec![],
+            rings: vec![],
+            link_sources: None,
         }
     }
 
@@ -163,6 +520,9 @@ mod tests {
             [output]
             dir = "./public"
 
+            [cache]
+            dir = "/tmp/graphgarden"
+
             [parse]
             include = ["**/*.html", "**/*.htm"]
             exclude = ["admin/**"]
@@ -180,10 +540,17 @@ mod tests {
         assert_eq!(config.site.language.as_deref(), Some("en"));
         assert_eq!(
             config.friends,
-            vec!["https://bob.dev/", "https://carol.dev/"]
+            vec![
+                Friend::from("https://bob.dev/"),
+                Friend::from("https://carol.dev/")
+            ]
         );
         assert_eq!(config.output.dir, "./public");
-        assert_eq!(config.parse.include, vec!["**/*.html", "**/*.htm"]);
+        assert_eq!(config.cache.dir, "/tmp/graphgarden");
+        assert_eq!(
+            config.parse.include,
+            Some(vec!["**/*.html".to_owned(), "**/*.htm".to_owned()])
+        );
         assert_eq!(config.parse.exclude, Some(vec!["admin/**".to_owned()]));
         assert_eq!(
             config.parse.exclude_selectors,
@@ -211,9 +578,196 @@ mod tests {
         assert_eq!(config.site.language, None);
         assert!(config.friends.is_empty());
         assert_eq!(config.output.dir, "./dist");
-        assert_eq!(config.parse.include, vec!["**/*.html"]);
-        assert_eq!(config.parse.exclude, None);
-        assert_eq!(config.parse.exclude_selectors, None);
+        assert_eq!(config.output.encoding, Encoding::Expanded);
+        assert_eq!(config.cache.dir, ".graphgarden-cache");
+        assert_eq!(config.parse, ParseConfig::default());
+        let parse = config.parse.effective();
+        assert_eq!(parse.include, vec!["**/*.html"]);
+        assert!(parse.exclude.is_empty());
+        assert!(parse.exclude_selectors.is_empty());
+        assert_eq!(parse.page_extensions, vec![".html"]);
+        assert!(config.groups.is_empty());
+        assert!(config.edge_types.is_empty());
+        assert!(config.contexts.is_empty());
+        assert_eq!(config.link_sources, None);
+    }
+
+    #[test]
+    fn preset_expands_and_explicit_values_replace_or_extend_it() {
+        let toml = r#"
+            [site]
+            base_url = "https://example.com/"
+            title = "My Site"
+
+            [parse]
+            preset = "jekyll"
+            exclude = ["drafts/**"]
+        "#;
+
+        let config = Config::from_str(toml).expect("config with a preset should parse");
+        assert_eq!(config.parse.preset, Some(Preset::Jekyll));
+        let parse = config.parse.effective();
+        assert_eq!(parse.include, vec!["**/*.html", "**/*.htm"]);
+        assert_eq!(parse.exclude, vec!["404.html", "drafts/**"]);
+        assert_eq!(parse.exclude_selectors, vec!["header", "footer", "nav"]);
+        assert_eq!(parse.page_extensions, vec![".html", ".htm"]);
+
+        let parse = ParseConfig {
+            preset: Some(Preset::MdBook),
+            include: Some(vec![String::from("docs/**/*.html")]),
+            exclude_selectors: Some(vec![String::from(".footnotes")]),
+            ..ParseConfig::default()
+        }
+        .effective();
+        assert_eq!(parse.include, vec!["docs/**/*.html"]);
+        assert_eq!(parse.exclude, vec!["404.html", "print.html", "toc.html"]);
+        assert_eq!(parse.exclude_selectors, vec![".footnotes"]);
+    }
+
+    #[test]
+    fn empty_exclude_selectors_clear_the_preset_selectors() {
+        let toml = r#"
+            [site]
+            base_url = "https://example.com/"
+            title = "My Site"
+
+            [parse]
+            preset = "hugo"
+            exclude_selectors = []
+        "#;
+
+        let config = Config::from_str(toml).expect("config with a preset should parse");
+        assert!(config.parse.effective().exclude_selectors.is_empty());
+    }
+
+    #[test]
+    fn every_preset_parses_from_its_key() {
+        for preset in Preset::ALL {
+            let toml = format!(
+                "[site]\nbase_url = \"https://example.com/\"\ntitle = \"My Site\"\n\n[parse]\npreset = \"{}\"\n",
+                preset.key()
+            );
+            let config = Config::from_str(&toml).unwrap();
+            assert_eq!(config.parse.preset, Some(preset));
+        }
+    }
+
+    #[test]
+    fn parse_groups() {
+        let toml = r#"
+            [site]
+            base_url = "https://example.com/"
+            title = "My Site"
+
+            [[groups]]
+            name = "home"
+            url = "/"
+
+            [[groups]]
+            name = "post"
+            path = "posts/**"
+            selector = "article"
+        "#;
+
+        let config = Config::from_str(toml).expect("config with groups should parse");
+
+        assert_eq!(
+            config.groups,
+            vec![
+                GroupConfig {
+                    name: String::from("home"),
+                    url: Some(String::from("/")),
+                    path: None,
+                    selector: None,
+                },
+                GroupConfig {
+                    name: String::from("post"),
+                    url: None,
+                    path: Some(String::from("posts/**")),
+                    selector: Some(String::from("article")),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_edge_types() {
+        let toml = r#"
+            [site]
+            base_url = "https://example.com/"
+            title = "My Site"
+
+            [[edge_types]]
+            selector = ".related-posts"
+            type = "related"
+        "#;
+
+        let config = Config::from_str(toml).expect("config with edge types should parse");
+
+        assert_eq!(
+            config.edge_types,
+            vec![EdgeTypeConfig {
+                selector: String::from(".related-posts"),
+                edge_type: String::from("related"),
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_contexts() {
+        let toml = r#"
+            [site]
+            base_url = "https://example.com/"
+            title = "My Site"
+
+            [[contexts]]
+            selector = ".breadcrumbs"
+            label = "breadcrumbs"
+        "#;
+
+        let config = Config::from_str(toml).expect("config with contexts should parse");
+
+        assert_eq!(
+            config.contexts,
+            vec![ContextConfig {
+                selector: String::from(".breadcrumbs"),
+                label: String::from("breadcrumbs"),
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_link_sources() {
+        let toml = r#"
+            [site]
+            base_url = "https://example.com/"
+            title = "My Site"
+
+            [[link_sources]]
+            selector = "a[href]"
+            attribute = "href"
+
+            [[link_sources]]
+            selector = "blockquote[cite]"
+            attribute = "cite"
+            type = "citation"
+            context = "quote"
+        "#;
+
+        let config = Config::from_str(toml).expect("config with link sources should parse");
+
+        assert_eq!(
+            config.link_sources,
+            Some(vec![
+                LinkSourceConfig::new("a[href]", "href", None),
+                LinkSourceConfig {
+                    selector: String::from("blockquote[cite]"),
+                    attribute: String::from("cite"),
+                    edge_type: Some(String::from("citation")),
+                    context: Some(String::from("quote")),
+                },
+            ])
+        );
     }
 
     #[test]
@@ -332,4 +886,223 @@ mod tests {
         let err = config.validate().unwrap_err();
         assert!(matches!(err, Error::InvalidFriendUrl(_, ref reason) if reason.contains("http")),);
     }
+
+    #[test]
+    fn parse_compact_output_encoding() {
+        let toml = r#"
+            [site]
+            base_url = "https://alice.dev/"
+            title = "Alice's Garden"
+
+            [output]
+            encoding = "compact"
+        "#;
+
+        let config = Config::from_str(toml).expect("compact encoding should parse");
+        assert_eq!(config.output.encoding, Encoding::Compact);
+        assert_eq!(config.output.dir, "./dist");
+    }
+
+    #[test]
+    fn parse_output_shard() {
+        let parse = |shard: &str| {
+            let toml = format!(
+                "[site]\nbase_url = \"https://alice.dev/\"\ntitle = \"Alice\"\n[output]\nshard = {shard}\n"
+            );
+            Config::from_str(&toml).map(|config| config.output.shard)
+        };
+
+        assert_eq!(parse("\"prefix\"").unwrap(), Some(ShardBy::Prefix));
+        assert_eq!(parse("5000").unwrap(), Some(ShardBy::Size(5000)));
+        assert!(parse("\"suffix\"").is_err());
+    }
+
+    #[test]
+    fn validate_rejects_empty_shards() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = test_config("https://alice.dev/", tmp.path().to_str().unwrap(), vec![]);
+        config.output.shard = Some(ShardBy::Size(0));
+
+        let err = config.validate().unwrap_err();
+        assert!(matches!(err, Error::InvalidShardSize));
+    }
+
+    #[test]
+    fn validate_rejects_empty_change_feed() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = test_config("https://alice.dev/", tmp.path().to_str().unwrap(), vec![]);
+        config.output.changes = Some(0);
+
+        let err = config.validate().unwrap_err();
+        assert!(matches!(err, Error::InvalidChangeGenerations));
+    }
+
+    #[test]
+    fn validate_rejects_out_of_range_compression_level() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = test_config("https://alice.dev/", tmp.path().to_str().unwrap(), vec![]);
+        config.output.gzip = Some(9);
+        config.output.brotli = Some(12);
+
+        let err = config.validate().unwrap_err();
+        assert!(matches!(
+            err,
+            Error::InvalidCompressionLevel("brotli", 12, 11)
+        ));
+    }
+
+    #[test]
+    fn parse_friend_tables() {
+        let toml = r#"
+            [site]
+            base_url = "https://alice.dev/"
+            title = "Alice's Garden"
+
+            [[friends]]
+            url = "https://bob.dev/"
+
+            [[friends]]
+            url = "https://carol.dev/"
+            name = "Carol"
+            description = "Carol's notes"
+            relationship = "friend met"
+            public_file = "https://carol.dev/graph.json"
+        "#;
+
+        let config = Config::from_str(toml).expect("friend tables should parse");
+
+        assert_eq!(config.friends[0], Friend::from("https://bob.dev/"));
+        assert_eq!(
+            config.friends[1],
+            Friend {
+                name: Some(String::from("Carol")),
+                description: Some(String::from("Carol's notes")),
+                relationship: Some(String::from("friend met")),
+                public_file: Some(String::from("https://carol.dev/graph.json")),
+                ..Friend::from("https://carol.dev/")
+            }
+        );
+    }
+
+    #[test]
+    fn validate_rejects_invalid_friend_public_file() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = test_config("https://alice.dev/", tmp.path().to_str().unwrap(), vec![]);
+        config.friends.push(Friend {
+            public_file: Some(String::from("graph.json")),
+            ..Friend::from("https://bob.dev/")
+        });
+
+        let err = config.validate().unwrap_err();
+        assert!(matches!(err, Error::InvalidFriendUrl(ref url, _) if url == "graph.json"));
+    }
+
+    #[test]
+    fn parse_inline_ring() {
+        let toml = r#"
+            [site]
+            base_url = "https://alice.dev/"
+            title = "Alice's Garden"
+
+            [[rings]]
+            manifest = "ring.toml"
+
+            [[rings]]
+            name = "Garden Ring"
+            nav = true
+            members = [{ url = "https://alice.dev/", name = "Alice" }, { url = "https://bob.dev/" }]
+        "#;
+
+        let config: Config = toml.parse().unwrap();
+        assert_eq!(config.rings[0].manifest.as_deref(), Some("ring.toml"));
+        assert!(!config.rings[0].nav);
+
+        let ring = config.rings[1].load().unwrap();
+        assert!(config.rings[1].nav);
+        assert_eq!(ring.name, "Garden Ring");
+        assert_eq!(ring.members[0].name.as_deref(), Some("Alice"));
+        assert_eq!(ring.members[1].url, "https://bob.dev/");
+    }
+
+    #[test]
+    fn validate_rejects_ring_without_manifest_or_members() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = test_config("https://alice.dev/", tmp.path().to_str().unwrap(), vec![]);
+        config.rings.push(RingConfig {
+            name: Some(String::from("Garden Ring")),
+            ..RingConfig::default()
+        });
+        let err = config.validate().unwrap_err();
+        assert!(matches!(err, Error::InvalidRing(ref name, _) if name == "Garden Ring"));
+
+        config.rings[0].manifest = Some(String::from("ring.toml"));
+        let err = config.validate().unwrap_err();
+        assert!(matches!(err, Error::InvalidRing(ref name, _) if name == "ring.toml"));
+    }
+
+    #[test]
+    fn validate_rejects_group_without_conditions() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = test_config("https://alice.dev/", tmp.path().to_str().unwrap(), vec![]);
+        config.groups.push(GroupConfig {
+            name: String::from("post"),
+            url: None,
+            path: None,
+            selector: None,
+        });
+        let err = config.validate().unwrap_err();
+        assert!(matches!(err, Error::InvalidGroup(ref name, _) if name == "post"));
+    }
+
+    #[test]
+    fn validate_rejects_group_with_empty_name() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = test_config("https://alice.dev/", tmp.path().to_str().unwrap(), vec![]);
+        config.groups.push(GroupConfig {
+            name: String::from(" "),
+            url: Some(String::from("/posts/**")),
+            path: None,
+            selector: None,
+        });
+        let err = config.validate().unwrap_err();
+        assert!(matches!(err, Error::InvalidGroup(..)));
+    }
+
+    #[test]
+    fn validate_rejects_builtin_edge_type_name() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = test_config("https://alice.dev/", tmp.path().to_str().unwrap(), vec![]);
+        config.edge_types.push(EdgeTypeConfig {
+            selector: String::from("nav"),
+            edge_type: String::from("friend"),
+        });
+        let err = config.validate().unwrap_err();
+        assert!(matches!(err, Error::InvalidEdgeType(ref name, _) if name == "friend"));
+    }
+
+    #[test]
+    fn validate_rejects_empty_edge_type_name() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = test_config("https://alice.dev/", tmp.path().to_str().unwrap(), vec![]);
+        config.edge_types.push(EdgeTypeConfig {
+            selector: String::from("nav"),
+            edge_type: String::new(),
+        });
+        let err = config.validate().unwrap_err();
+        assert!(matches!(err, Error::InvalidEdgeType(..)));
+    }
+
+    #[test]
+    fn validate_rejects_builtin_link_source_type() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut config = test_config("https://alice.dev/", tmp.path().to_str().unwrap(), vec![]);
+        config.link_sources = Some(vec![LinkSourceConfig {
+            selector: String::from("blockquote[cite]"),
+            attribute: String::from("cite"),
+            edge_type: Some(String::from("internal")),
+            context: None,
+        }]);
+        let err = config.validate().unwrap_err();
+        assert!(matches!(err, Error::InvalidEdgeType(..)));
+    }
 }
@@ -24,14 +24,82 @@ pub enum Error {
     #[error("invalid friend URL '{0}': {1}")]
     InvalidFriendUrl(String, String),
 
+    #[error("invalid group '{0}': {1}")]
+    InvalidGroup(String, String),
+
+    #[error("invalid edge type '{0}': {1}")]
+    InvalidEdgeType(String, String),
+
+    #[error("invalid URL '{0}': {1}")]
+    InvalidFetchUrl(String, String),
+
+    #[error("failed to fetch {0}: {1}")]
+    Fetch(String, String),
+
+    #[error("invalid URL '{0}' in a merged file: {1}")]
+    InvalidMergeUrl(String, String),
+
+    #[error("invalid friend mount '{0}': {1}")]
+    InvalidFriendMount(String, String),
+
+    #[error("failed to serve on {0}: {1}")]
+    Serve(String, String),
+
+    #[error("invalid SOURCE_DATE_EPOCH '{0}': expected a Unix timestamp in seconds")]
+    InvalidSourceDateEpoch(String),
+
+    #[error("invalid {0} compression level {1}: must be between 0 and {2}")]
+    InvalidCompressionLevel(&'static str, u32, u32),
+
+    #[error("invalid shard size 0: shards must hold at least one node")]
+    InvalidShardSize,
+
+    #[error("invalid number of change generations 0: set at least 1, or remove `changes`")]
+    InvalidChangeGenerations,
+
+    #[error("failed to generate a signing key: {0}")]
+    KeyGeneration(String),
+
+    #[error("invalid signing key in {0}: expected the base64 of a 32-byte ed25519 seed")]
+    InvalidSigningKey(PathBuf),
+
+    #[error("invalid public key '{0}': expected the base64 of a 32-byte ed25519 public key")]
+    InvalidPublicKey(String),
+
+    #[error("the file declares no public key to verify its signature with")]
+    MissingPublicKey,
+
+    #[error("the file declares public key '{0}', but '{1}' is pinned")]
+    PublicKeyMismatch(String, String),
+
+    #[error("invalid signature")]
+    InvalidSignature,
+
+    #[error("invalid ring '{0}': {1}")]
+    InvalidRing(String, String),
+
+    #[error("failed to parse ring manifest {0}: {1}")]
+    RingParse(PathBuf, #[source] toml::de::Error),
+
+    #[error("'{1}' is not a member of the ring '{0}'")]
+    NotARingMember(String, String),
+
+    #[error("failed to serialize TOML: {0}")]
+    TomlSerialize(#[from] toml::ser::Error),
+
+    #[error("compression failed: {0}")]
+    Compress(#[source] std::io::Error),
+
+    #[error(
+        "{0} compression is unavailable: graphgarden-core was built without the `compress` feature"
+    )]
+    CompressionUnavailable(&'static str),
+
     #[error("output directory does not exist: {0}")]
     OutputDirNotFound(PathBuf),
 
-    #[error("failed to serialize JSON: {0}")]
-    JsonSerialize(#[source] serde_json::Error),
-
-    #[error("failed to deserialize JSON: {0}")]
-    JsonDeserialize(#[source] serde_json::Error),
+    #[error(transparent)]
+    Protocol(#[from] graphgarden_protocol::Error),
 
     #[error("HTML parsing failed: {0}")]
     HtmlParse(String),
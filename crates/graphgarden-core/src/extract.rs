@@ -9,8 +9,53 @@ use lol_html::{
     text,
 };
 
+use crate::config::{
+    ContextConfig, DEFAULT_LINK_SOURCES, DEFAULT_PAGE_EXTENSIONS, EdgeTypeConfig, LinkSourceConfig,
+};
 use crate::error::{Error, Result};
-use crate::model::{Edge, EdgeType, Node};
+use crate::model::{Edge, EdgeType, Extensions, Node};
+
+/// Site-wide settings for [`extract_page`], shared by every page of a build.
+#[derive(Debug, Clone, Copy)]
+pub struct ExtractOptions<'a> {
+    pub base_url: &'a str,
+    pub friends: &'a [String],
+    /// Links inside elements matching these CSS selectors are ignored.
+    pub exclude_selectors: &'a [String],
+    /// Links inside elements matching these rules get a custom edge type.
+    pub edge_types: &'a [EdgeTypeConfig],
+    /// Extra context labels, on top of [`LANDMARK_CONTEXTS`].
+    pub contexts: &'a [ContextConfig],
+    /// Elements and attributes that links are read from.
+    pub link_sources: &'a [LinkSourceConfig],
+    /// Extensions stripped from internal links, e.g. `/about.html` → `/about`.
+    pub page_extensions: &'a [String],
+}
+
+impl<'a> ExtractOptions<'a> {
+    /// Options with no excluded selectors, no custom edge types or contexts,
+    /// the [`DEFAULT_LINK_SOURCES`] and the [`DEFAULT_PAGE_EXTENSIONS`].
+    pub fn new(base_url: &'a str, friends: &'a [String]) -> Self {
+        Self {
+            base_url,
+            friends,
+            exclude_selectors: &[],
+            edge_types: &[],
+            contexts: &[],
+            link_sources: &DEFAULT_LINK_SOURCES,
+            page_extensions: &DEFAULT_PAGE_EXTENSIONS,
+        }
+    }
+}
+
+/// Landmark elements (and their ARIA role equivalents) that give an edge its `context`.
+pub const LANDMARK_CONTEXTS: &[(&str, &str)] = &[
+    ("nav, [role=navigation]", "nav"),
+    ("header, [role=banner]", "header"),
+    ("main, [role=main]", "main"),
+    ("aside, [role=complementary]", "aside"),
+    ("footer, [role=contentinfo]", "footer"),
+];
 
 /// Helper to build an end-tag handler with the correct `Box<dyn FnOnce>` type.
 fn end_tag_handler(
@@ -21,20 +66,23 @@ fn end_tag_handler(
 
 /// Extracts a page node and its outgoing edges from HTML content.
 ///
-/// Links inside elements matching `exclude_selectors` are ignored.
+/// Links inside elements matching `exclude_selectors` are ignored, and internal
+/// links inside elements matching an `edge_types` rule are typed after the
+/// innermost one. Friend links keep the `friend` type.
+/// Each edge's `context` is the label of the innermost enclosing landmark or
+/// `contexts` rule. Links are read from every `link_sources` entry.
 /// External links that don't match any friend URL are dropped.
+/// The title is entity-decoded and whitespace-collapsed, see [`normalize_text`].
 pub fn extract_page(
     html: &str,
     page_url: &str,
-    base_url: &str,
-    friends: &[String],
-    exclude_selectors: &[String],
+    options: &ExtractOptions<'_>,
 ) -> Result<(Node, Vec<Edge>)> {
     let title_text = Rc::new(RefCell::new(String::new()));
     let title_done = Rc::new(Cell::new(false));
     let excluded_depth = Rc::new(Cell::new(0usize));
-    let collected_edges = Rc::new(RefCell::new(Vec::<Edge>::new()));
-    let seen_targets = Rc::new(RefCell::new(HashSet::<String>::new()));
+    let active_edge_types = Rc::new(RefCell::new(Vec::<usize>::new()));
+    let active_contexts = Rc::new(RefCell::new(Vec::<usize>::new()));
 
     let mut handlers: Vec<(Cow<lol_html::Selector>, ElementContentHandlers)> = Vec::new();
 
@@ -68,10 +116,8 @@ pub fn extract_page(
     }
 
     // Depth counter for excluded CSS selectors — links inside are skipped
-    for selector_str in exclude_selectors {
-        let selector = selector_str.parse::<lol_html::Selector>().map_err(|err| {
-            Error::HtmlParse(format!("invalid CSS selector '{selector_str}': {err}"))
-        })?;
+    for selector_str in options.exclude_selectors {
+        let selector = parse_selector(selector_str)?;
         let depth = Rc::clone(&excluded_depth);
         handlers.push((
             Cow::Owned(selector),
@@ -89,32 +135,63 @@ pub fn extract_page(
         ));
     }
 
-    // Collect <a href="..."> links, deduplicating by target
-    {
+    // Stacks of open elements matching an edge type or context rule — registered
+    // before the link handlers so a rule whose selector matches the link itself applies too
+    for (index, rule) in options.edge_types.iter().enumerate() {
+        let selector = parse_selector(&rule.selector)?;
+        handlers.push(track_open_elements(selector, &active_edge_types, index));
+    }
+
+    let context_rules = LANDMARK_CONTEXTS.iter().copied().chain(
+        options
+            .contexts
+            .iter()
+            .map(|rule| (rule.selector.as_str(), rule.label.as_str())),
+    );
+    let mut context_labels = Vec::new();
+    for (index, (selector, label)) in context_rules.enumerate() {
+        handlers.push(track_open_elements(
+            parse_selector(selector)?,
+            &active_contexts,
+            index,
+        ));
+        context_labels.push(label.to_owned());
+    }
+
+    // Collect links from every link source, deduplicating by target
+    let collector = Rc::new(LinkCollector {
+        page_url: page_url.to_owned(),
+        base_url: options.base_url.to_owned(),
+        friends: options.friends.to_vec(),
+        page_extensions: options.page_extensions.to_vec(),
+        custom_types: options
+            .edge_types
+            .iter()
+            .map(|rule| rule.edge_type.clone())
+            .collect(),
+        context_labels,
+        active_edge_types,
+        active_contexts,
+        seen_targets: RefCell::new(HashSet::new()),
+        edges: RefCell::new(Vec::new()),
+    });
+    for source in options.link_sources {
+        let selector = parse_selector(&source.selector)?;
         let depth = Rc::clone(&excluded_depth);
-        let edges = Rc::clone(&collected_edges);
-        let seen = Rc::clone(&seen_targets);
-        let page = page_url.to_owned();
-        let base = base_url.to_owned();
-        let friends_owned = friends.to_vec();
-
-        handlers.push(element!("a[href]", move |el| {
-            if depth.get() > 0 {
-                return Ok(());
-            }
-            if let Some(href) = el.get_attribute("href")
-                && let Some((target, edge_type)) =
-                    classify_href(&href, &page, &base, &friends_owned)
-                && seen.borrow_mut().insert(target.clone())
-            {
-                edges.borrow_mut().push(Edge {
-                    source: page.clone(),
-                    target,
-                    edge_type,
-                });
-            }
-            Ok(())
-        }));
+        let collector = Rc::clone(&collector);
+        let source = source.clone();
+        handlers.push((
+            Cow::Owned(selector),
+            ElementContentHandlers::default().element(move |el: &mut Element| {
+                if depth.get() > 0 {
+                    return Ok(());
+                }
+                if let Some(href) = el.get_attribute(&source.attribute) {
+                    collector.collect(&href, &source);
+                }
+                Ok(())
+            }),
+        ));
     }
 
     rewrite_str(
@@ -127,27 +204,185 @@ pub fn extract_page(
     .map_err(|err| Error::HtmlParse(err.to_string()))?;
 
     let title = {
-        let t = title_text.borrow();
-        let trimmed = t.trim();
-        if trimmed.is_empty() {
+        let normalized = normalize_text(&title_text.borrow());
+        if normalized.is_empty() {
             page_url.to_owned()
         } else {
-            trimmed.to_owned()
+            normalized
         }
     };
 
     let node = Node {
         url: page_url.to_owned(),
         title,
+        group: None,
+        extensions: Extensions::new(),
     };
 
-    let edges = Rc::try_unwrap(collected_edges)
+    let edges = Rc::try_unwrap(collector)
+        .ok()
         .expect("all handler references are dropped after rewrite_str")
+        .edges
         .into_inner();
 
     Ok((node, edges))
 }
 
+/// Reports, for each CSS selector, whether it matches at least one element in `html`.
+pub fn matching_selectors(html: &str, selectors: &[String]) -> Result<Vec<bool>> {
+    let matched = Rc::new(RefCell::new(vec![false; selectors.len()]));
+
+    let mut handlers: Vec<(Cow<lol_html::Selector>, ElementContentHandlers)> = Vec::new();
+    for (index, selector_str) in selectors.iter().enumerate() {
+        let selector = parse_selector(selector_str)?;
+        let flags = Rc::clone(&matched);
+        handlers.push((
+            Cow::Owned(selector),
+            ElementContentHandlers::default().element(move |_: &mut Element| {
+                flags.borrow_mut()[index] = true;
+                Ok(())
+            }),
+        ));
+    }
+
+    rewrite_str(
+        html,
+        RewriteStrSettings {
+            element_content_handlers: handlers,
+            ..RewriteStrSettings::new()
+        },
+    )
+    .map_err(|err| Error::HtmlParse(err.to_string()))?;
+
+    Ok(Rc::try_unwrap(matched)
+        .expect("all handler references are dropped after rewrite_str")
+        .into_inner())
+}
+
+/// Turns link attribute values into edges, shared by every link source handler.
+struct LinkCollector {
+    page_url: String,
+    base_url: String,
+    friends: Vec<String>,
+    page_extensions: Vec<String>,
+    custom_types: Vec<String>,
+    context_labels: Vec<String>,
+    active_edge_types: Rc<RefCell<Vec<usize>>>,
+    active_contexts: Rc<RefCell<Vec<usize>>>,
+    seen_targets: RefCell<HashSet<String>>,
+    edges: RefCell<Vec<Edge>>,
+}
+
+impl LinkCollector {
+    /// Classifies `href` and records it as an edge, unless it is dropped or already seen.
+    ///
+    /// The source's own `type` and `context` take precedence over enclosing rules.
+    /// Custom types only replace `internal`: friend links always stay `friend`,
+    /// so their absolute targets remain valid and recognizable as friends.
+    fn collect(&self, href: &str, source: &LinkSourceConfig) {
+        let Some((target, edge_type)) = classify_href(
+            &decode_entities(href),
+            &self.page_url,
+            &self.base_url,
+            &self.friends,
+            &self.page_extensions,
+        ) else {
+            return;
+        };
+        if !self.seen_targets.borrow_mut().insert(target.clone()) {
+            return;
+        }
+
+        let edge_type = match (&source.edge_type, self.active_edge_types.borrow().last()) {
+            _ if edge_type == EdgeType::Friend => edge_type,
+            (Some(name), _) => EdgeType::from(name.clone()),
+            (None, Some(&index)) => EdgeType::from(self.custom_types[index].clone()),
+            (None, None) => edge_type,
+        };
+        let context = source.context.clone().or_else(|| {
+            self.active_contexts
+                .borrow()
+                .last()
+                .map(|&index| self.context_labels[index].clone())
+        });
+
+        self.edges.borrow_mut().push(Edge {
+            source: self.page_url.clone(),
+            target,
+            edge_type,
+            context,
+            extensions: Extensions::new(),
+        });
+    }
+}
+
+/// Builds a handler keeping `index` on `stack` while an element matching `selector` is open,
+/// so the top of the stack is the innermost matching ancestor.
+fn track_open_elements(
+    selector: lol_html::Selector,
+    stack: &Rc<RefCell<Vec<usize>>>,
+    index: usize,
+) -> (
+    Cow<'static, lol_html::Selector>,
+    ElementContentHandlers<'static>,
+) {
+    let stack = Rc::clone(stack);
+    (
+        Cow::Owned(selector),
+        ElementContentHandlers::default().element(move |el: &mut Element| {
+            // Void elements have no end tag to pop on, and no content to track
+            if let Some(h) = el.end_tag_handlers() {
+                stack.borrow_mut().push(index);
+                let s = Rc::clone(&stack);
+                h.push(end_tag_handler(move |_| {
+                    s.borrow_mut().pop();
+                    Ok(())
+                }));
+            }
+            Ok(())
+        }),
+    )
+}
+
+fn parse_selector(selector: &str) -> Result<lol_html::Selector> {
+    selector
+        .parse::<lol_html::Selector>()
+        .map_err(|err| Error::HtmlParse(format!("invalid CSS selector '{selector}': {err}")))
+}
+
+// ---------------------------------------------------------------------------
+// Text normalization helpers
+// ---------------------------------------------------------------------------
+
+/// Turns raw extracted text into a single clean line.
+///
+/// Decodes HTML entities (lol_html hands us raw source text), drops control
+/// and bidi-override characters so titles are safe to render, and collapses
+/// runs of whitespace into single spaces.
+pub fn normalize_text(raw: &str) -> String {
+    let cleaned: String = decode_entities(raw)
+        .chars()
+        .filter(|c| c.is_whitespace() || !is_unsafe_char(*c))
+        .collect();
+
+    cleaned.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Decodes named and numeric HTML character references.
+fn decode_entities(raw: &str) -> Cow<'_, str> {
+    html_escape::decode_html_entities(raw)
+}
+
+/// Control characters and invisible bidi formatting characters, which can
+/// reorder or hide surrounding text when rendered.
+fn is_unsafe_char(c: char) -> bool {
+    c.is_control()
+        || matches!(
+            c,
+            '\u{061C}' | '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}'
+        )
+}
+
 // ---------------------------------------------------------------------------
 // URL classification helpers
 // ---------------------------------------------------------------------------
@@ -158,6 +393,7 @@ fn classify_href(
     page_url: &str,
     base_url: &str,
     friends: &[String],
+    extensions: &[String],
 ) -> Option<(String, EdgeType)> {
     let href = href.trim();
 
@@ -175,24 +411,24 @@ fn classify_href(
     // Protocol-relative URL — treat as https
     if href.starts_with("//") {
         let absolute = format!("https:{href}");
-        return classify_absolute_url(&absolute, base_url, friends);
+        return classify_absolute_url(&absolute, base_url, friends, extensions);
     }
 
     if href.starts_with("http://") || href.starts_with("https://") {
-        return classify_absolute_url(href, base_url, friends);
+        return classify_absolute_url(href, base_url, friends, extensions);
     }
 
     // Absolute path
     if href.starts_with('/') {
         let clean = strip_query_and_fragment(href);
-        let normalized = normalize_internal_path(clean);
+        let normalized = normalize_internal_path(clean, extensions);
         return Some((normalized, EdgeType::Internal));
     }
 
     // Relative path — resolve against the current page
     let clean = strip_query_and_fragment(href);
     let resolved = resolve_relative_url(page_url, clean);
-    let normalized = normalize_internal_path(&resolved);
+    let normalized = normalize_internal_path(&resolved, extensions);
     Some((normalized, EdgeType::Internal))
 }
 
@@ -200,6 +436,7 @@ fn classify_absolute_url(
     href: &str,
     base_url: &str,
     friends: &[String],
+    extensions: &[String],
 ) -> Option<(String, EdgeType)> {
     let clean = strip_query_and_fragment(href);
     let base = base_url.trim_end_matches('/');
@@ -211,7 +448,7 @@ fn classify_absolute_url(
     if let Some(rest) = clean.strip_prefix(base)
         && rest.starts_with('/')
     {
-        let normalized = normalize_internal_path(rest);
+        let normalized = normalize_internal_path(rest, extensions);
         return Some((normalized, EdgeType::Internal));
     }
 
@@ -233,15 +470,17 @@ fn classify_absolute_url(
 
 /// Normalizes internal paths to match the URL form produced by `file_path_to_url`.
 /// Without this, edge targets like `/about/index.html` would never match node URLs.
-fn normalize_internal_path(path: &str) -> String {
-    if path.ends_with("/index.html") {
-        let mut result = path[..path.len() - "index.html".len()].to_owned();
-        if result.is_empty() {
-            result.push('/');
+/// The first matching page extension is stripped.
+pub(crate) fn normalize_internal_path(path: &str, extensions: &[String]) -> String {
+    for extension in extensions {
+        let Some(stripped) = path.strip_suffix(extension.as_str()) else {
+            continue;
+        };
+        if let Some(dir) = stripped.strip_suffix("index")
+            && dir.ends_with('/')
+        {
+            return dir.to_owned();
         }
-        return result;
-    }
-    if let Some(stripped) = path.strip_suffix(".html") {
         return stripped.to_owned();
     }
     path.to_owned()
@@ -291,6 +530,17 @@ mod tests {
         ]
     }
 
+    fn options(friends: &[String]) -> ExtractOptions<'_> {
+        ExtractOptions::new(BASE_URL, friends)
+    }
+
+    fn edge_type_rule(selector: &str, edge_type: &str) -> EdgeTypeConfig {
+        EdgeTypeConfig {
+            selector: String::from(selector),
+            edge_type: String::from(edge_type),
+        }
+    }
+
     #[test]
     fn extract_internal_links() {
         let html = r#"
@@ -301,7 +551,7 @@ mod tests {
             </body></html>
         "#;
 
-        let (node, edges) = extract_page(html, "/", BASE_URL, &friends(), &[]).unwrap();
+        let (node, edges) = extract_page(html, "/", &options(&friends())).unwrap();
 
         assert_eq!(node.url, "/");
         assert_eq!(node.title, "Home");
@@ -321,7 +571,7 @@ mod tests {
             </body></html>
         "#;
 
-        let (_, edges) = extract_page(html, "/", BASE_URL, &friends(), &[]).unwrap();
+        let (_, edges) = extract_page(html, "/", &options(&friends())).unwrap();
 
         assert_eq!(edges.len(), 2);
         assert!(edges.iter().all(|e| e.edge_type == EdgeType::Friend));
@@ -338,7 +588,7 @@ mod tests {
             </body></html>
         "#;
 
-        let (_, edges) = extract_page(html, "/", BASE_URL, &friends(), &[]).unwrap();
+        let (_, edges) = extract_page(html, "/", &options(&friends())).unwrap();
 
         assert_eq!(edges.len(), 1);
         assert_eq!(edges[0].target, "/about");
@@ -357,7 +607,267 @@ mod tests {
         "#;
 
         let selectors = vec![String::from("nav"), String::from("footer")];
-        let (_, edges) = extract_page(html, "/", BASE_URL, &friends(), &selectors).unwrap();
+        let friends = friends();
+        let options = ExtractOptions {
+            exclude_selectors: &selectors,
+            ..options(&friends)
+        };
+        let (_, edges) = extract_page(html, "/", &options).unwrap();
+
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].target, "/visible");
+    }
+
+    #[test]
+    fn extract_custom_edge_types() {
+        let html = r##"
+            <html><head><title>Post</title></head>
+            <body>
+                <main>
+                    <a href="/about">About</a>
+                    <a class="footnote-ref" href="/sources/1">1</a>
+                </main>
+                <aside class="related-posts">
+                    <a href="/posts/other">Other post</a>
+                    <a href="https://bob.dev/posts/similar">Bob's similar post</a>
+                </aside>
+                <nav class="series-nav"><a href="/posts/part-2">Part 2</a></nav>
+            </body></html>
+        "##;
+
+        let friends = friends();
+        let rules = vec![
+            edge_type_rule(".related-posts", "related"),
+            edge_type_rule(".series-nav", "series"),
+            edge_type_rule("a.footnote-ref", "citation"),
+        ];
+        let options = ExtractOptions {
+            edge_types: &rules,
+            ..options(&friends)
+        };
+        let (_, edges) = extract_page(html, "/posts/hello", &options).unwrap();
+
+        let type_of = |target: &str| {
+            edges
+                .iter()
+                .find(|e| e.target == target)
+                .map(|e| e.edge_type.clone())
+        };
+
+        assert_eq!(type_of("/about"), Some(EdgeType::Internal));
+        assert_eq!(
+            type_of("/sources/1"),
+            Some(EdgeType::Custom(String::from("citation")))
+        );
+        assert_eq!(
+            type_of("/posts/other"),
+            Some(EdgeType::Custom(String::from("related")))
+        );
+        assert_eq!(
+            type_of("https://bob.dev/posts/similar"),
+            Some(EdgeType::Friend),
+            "friend links keep their type inside a typed region"
+        );
+        assert_eq!(
+            type_of("/posts/part-2"),
+            Some(EdgeType::Custom(String::from("series")))
+        );
+    }
+
+    #[test]
+    fn extract_nested_edge_types_use_innermost_rule() {
+        let html = r#"
+            <html><head><title>Post</title></head>
+            <body>
+                <section class="related-posts">
+                    <a href="/posts/other">Other</a>
+                    <div class="series-nav"><a href="/posts/part-2">Part 2</a></div>
+                    <a href="/posts/another">Another</a>
+                </section>
+            </body></html>
+        "#;
+
+        let friends = friends();
+        let rules = vec![
+            edge_type_rule(".related-posts", "related"),
+            edge_type_rule(".series-nav", "series"),
+        ];
+        let options = ExtractOptions {
+            edge_types: &rules,
+            ..options(&friends)
+        };
+        let (_, edges) = extract_page(html, "/", &options).unwrap();
+
+        let types: Vec<&str> = edges.iter().map(|e| e.edge_type.as_str()).collect();
+        assert_eq!(types, vec!["related", "series", "related"]);
+    }
+
+    #[test]
+    fn extract_rejects_invalid_edge_type_selector() {
+        let html = "<html><body><a href=\"/about\">link</a></body></html>";
+        let friends = friends();
+        let rules = vec![edge_type_rule("a]", "related")];
+        let options = ExtractOptions {
+            edge_types: &rules,
+            ..options(&friends)
+        };
+
+        let result = extract_page(html, "/", &options);
+
+        assert!(matches!(result, Err(Error::HtmlParse(_))));
+    }
+
+    #[test]
+    fn extract_context_from_landmarks() {
+        let html = r#"
+            <html><head><title>Home</title></head>
+            <body>
+                <header><a href="/">Logo</a></header>
+                <nav><a href="/about">About</a></nav>
+                <main>
+                    <a href="/posts/hello">Hello</a>
+                    <aside><a href="/posts/aside">Aside</a></aside>
+                </main>
+                <div role="contentinfo"><a href="/legal">Legal</a></div>
+                <a href="/loose">Loose</a>
+            </body></html>
+        "#;
+
+        let (_, edges) = extract_page(html, "/posts/x", &options(&friends())).unwrap();
+
+        let context_of = |target: &str| {
+            edges
+                .iter()
+                .find(|e| e.target == target)
+                .and_then(|e| e.context.as_deref())
+        };
+
+        assert_eq!(context_of("/"), Some("header"));
+        assert_eq!(context_of("/about"), Some("nav"));
+        assert_eq!(context_of("/posts/hello"), Some("main"));
+        assert_eq!(context_of("/posts/aside"), Some("aside"));
+        assert_eq!(context_of("/legal"), Some("footer"));
+        assert_eq!(context_of("/loose"), None);
+    }
+
+    #[test]
+    fn extract_context_from_configured_rules() {
+        let html = r#"
+            <html><head><title>Home</title></head>
+            <body>
+                <nav class="breadcrumbs"><a href="/docs/">Docs</a></nav>
+                <main><div class="toc"><a href="/docs/intro">Intro</a></div></main>
+            </body></html>
+        "#;
+
+        let friends = friends();
+        let contexts = vec![
+            ContextConfig {
+                selector: String::from(".breadcrumbs"),
+                label: String::from("breadcrumbs"),
+            },
+            ContextConfig {
+                selector: String::from(".toc"),
+                label: String::from("toc"),
+            },
+        ];
+        let options = ExtractOptions {
+            contexts: &contexts,
+            ..options(&friends)
+        };
+        let (_, edges) = extract_page(html, "/", &options).unwrap();
+
+        let contexts: Vec<Option<&str>> = edges.iter().map(|e| e.context.as_deref()).collect();
+        assert_eq!(contexts, vec![Some("breadcrumbs"), Some("toc")]);
+    }
+
+    #[test]
+    fn extract_default_link_sources() {
+        let html = r##"
+            <html><head>
+                <title>Part 2</title>
+                <link rel="prev" href="/series/part-1">
+                <link rel="next" href="/series/part-3">
+                <link rel="stylesheet" href="/style.css">
+            </head>
+            <body>
+                <img usemap="#map"><map name="map"><area href="/map/north" alt="North"></map>
+                <svg><a xlink:href="/diagram/node"><text>Node</text></a></svg>
+                <blockquote cite="/quotes/source">Quote</blockquote>
+            </body></html>
+        "##;
+
+        let (_, edges) = extract_page(html, "/series/part-2", &options(&friends())).unwrap();
+
+        let targets: Vec<&str> = edges.iter().map(|e| e.target.as_str()).collect();
+        assert_eq!(
+            targets,
+            vec![
+                "/series/part-1",
+                "/series/part-3",
+                "/map/north",
+                "/diagram/node"
+            ]
+        );
+        assert_eq!(edges[0].context.as_deref(), Some("pagination"));
+        assert_eq!(edges[1].context.as_deref(), Some("pagination"));
+        assert!(edges.iter().all(|e| e.edge_type == EdgeType::Internal));
+    }
+
+    #[test]
+    fn extract_configured_link_sources() {
+        let html = r#"
+            <html><head><title>Quotes</title></head>
+            <body>
+                <main>
+                    <blockquote cite="/posts/wisdom">Quote</blockquote>
+                    <blockquote cite="https://bob.dev/posts/wisdom">Quote</blockquote>
+                    <a href="/about">About</a>
+                </main>
+            </body></html>
+        "#;
+
+        let friends = friends();
+        let sources = vec![LinkSourceConfig {
+            selector: String::from("blockquote[cite]"),
+            attribute: String::from("cite"),
+            edge_type: Some(String::from("citation")),
+            context: None,
+        }];
+        let options = ExtractOptions {
+            link_sources: &sources,
+            ..options(&friends)
+        };
+        let (_, edges) = extract_page(html, "/", &options).unwrap();
+
+        assert_eq!(edges.len(), 2, "a[href] is not a source anymore");
+        assert_eq!(edges[0].target, "/posts/wisdom");
+        assert_eq!(
+            edges[0].edge_type,
+            EdgeType::Custom(String::from("citation"))
+        );
+        assert_eq!(edges[0].context.as_deref(), Some("main"));
+        assert_eq!(edges[1].target, "https://bob.dev/posts/wisdom");
+        assert_eq!(edges[1].edge_type, EdgeType::Friend);
+    }
+
+    #[test]
+    fn extract_link_sources_respect_excluded_selectors() {
+        let html = r#"
+            <html><head><title>Map</title></head>
+            <body>
+                <footer><map name="m"><area href="/hidden"></map></footer>
+                <map name="n"><area href="/visible"></map>
+            </body></html>
+        "#;
+
+        let friends = friends();
+        let selectors = vec![String::from("footer")];
+        let options = ExtractOptions {
+            exclude_selectors: &selectors,
+            ..options(&friends)
+        };
+        let (_, edges) = extract_page(html, "/", &options).unwrap();
 
         assert_eq!(edges.len(), 1);
         assert_eq!(edges[0].target, "/visible");
@@ -370,16 +880,69 @@ mod tests {
             <body></body></html>
         "#;
 
-        let (node, _) = extract_page(html, "/page", BASE_URL, &friends(), &[]).unwrap();
+        let (node, _) = extract_page(html, "/page", &options(&friends())).unwrap();
 
         assert_eq!(node.title, "My Page Title");
     }
 
+    #[test]
+    fn extract_title_decodes_entities() {
+        let html = r#"
+            <html><head><title>Tom &amp; Jerry &#8212; &#x201C;Quotes&#x201D; &hellip;</title></head>
+            <body></body></html>
+        "#;
+
+        let (node, _) = extract_page(html, "/page", &options(&friends())).unwrap();
+
+        assert_eq!(node.title, "Tom & Jerry — “Quotes” …");
+    }
+
+    #[test]
+    fn extract_title_collapses_whitespace() {
+        let html =
+            "<html><head><title>\n  My\n\tMultiline   Title\n</title></head><body></body></html>";
+
+        let (node, _) = extract_page(html, "/page", &options(&friends())).unwrap();
+
+        assert_eq!(node.title, "My Multiline Title");
+    }
+
+    #[test]
+    fn extract_title_strips_control_and_bidi_characters() {
+        let html = "<html><head><title>Safe\u{202E}txt.exe\u{0007} &#x2066;Title&#x2069;</title></head><body></body></html>";
+
+        let (node, _) = extract_page(html, "/page", &options(&friends())).unwrap();
+
+        assert_eq!(node.title, "Safetxt.exe Title");
+    }
+
+    #[test]
+    fn extract_title_fallback_when_only_whitespace_and_control() {
+        let html = "<html><head><title> &#x202E; \u{0000} </title></head><body></body></html>";
+
+        let (node, _) = extract_page(html, "/page", &options(&friends())).unwrap();
+
+        assert_eq!(node.title, "/page");
+    }
+
+    #[test]
+    fn extract_decodes_entities_in_href() {
+        let html = r#"
+            <html><head><title>Home</title></head>
+            <body><a href="/tom&amp;jerry">Tom and Jerry</a></body></html>
+        "#;
+
+        let (_, edges) = extract_page(html, "/", &options(&friends())).unwrap();
+
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].target, "/tom&jerry");
+    }
+
     #[test]
     fn extract_title_fallback() {
         let html = "<html><body><a href=\"/about\">link</a></body></html>";
 
-        let (node, _) = extract_page(html, "/page", BASE_URL, &friends(), &[]).unwrap();
+        let (node, _) = extract_page(html, "/page", &options(&friends())).unwrap();
 
         assert_eq!(node.title, "/page");
     }
@@ -395,7 +958,7 @@ mod tests {
             </body></html>
         "#;
 
-        let (_, edges) = extract_page(html, "/", BASE_URL, &friends(), &[]).unwrap();
+        let (_, edges) = extract_page(html, "/", &options(&friends())).unwrap();
 
         assert_eq!(edges.len(), 1);
         assert_eq!(edges[0].target, "/about");
@@ -408,7 +971,7 @@ mod tests {
             <body><a href="../about">About</a></body></html>
         "#;
 
-        let (_, edges) = extract_page(html, "/posts/hello", BASE_URL, &friends(), &[]).unwrap();
+        let (_, edges) = extract_page(html, "/posts/hello", &options(&friends())).unwrap();
 
         assert_eq!(edges.len(), 1);
         assert_eq!(edges[0].target, "/about");
@@ -422,7 +985,7 @@ mod tests {
             <body><a href="https://alice.dev/about">About</a></body></html>
         "#;
 
-        let (_, edges) = extract_page(html, "/", BASE_URL, &friends(), &[]).unwrap();
+        let (_, edges) = extract_page(html, "/", &options(&friends())).unwrap();
 
         assert_eq!(edges.len(), 1);
         assert_eq!(edges[0].target, "/about");
@@ -441,7 +1004,7 @@ mod tests {
             </body></html>
         "##;
 
-        let (_, edges) = extract_page(html, "/", BASE_URL, &friends(), &[]).unwrap();
+        let (_, edges) = extract_page(html, "/", &options(&friends())).unwrap();
 
         assert_eq!(edges.len(), 1);
         assert_eq!(edges[0].target, "/real");
@@ -454,7 +1017,7 @@ mod tests {
             <body><a href="/about/index.html">About</a></body></html>
         "#;
 
-        let (_, edges) = extract_page(html, "/", BASE_URL, &friends(), &[]).unwrap();
+        let (_, edges) = extract_page(html, "/", &options(&friends())).unwrap();
 
         assert_eq!(edges.len(), 1);
         assert_eq!(edges[0].target, "/about/");
@@ -468,7 +1031,7 @@ mod tests {
             <body><a href="/posts/hello.html">Hello</a></body></html>
         "#;
 
-        let (_, edges) = extract_page(html, "/", BASE_URL, &friends(), &[]).unwrap();
+        let (_, edges) = extract_page(html, "/", &options(&friends())).unwrap();
 
         assert_eq!(edges.len(), 1);
         assert_eq!(edges[0].target, "/posts/hello");
@@ -482,7 +1045,7 @@ mod tests {
             <body><a href="https://alice.dev/about/index.html">About</a></body></html>
         "#;
 
-        let (_, edges) = extract_page(html, "/", BASE_URL, &friends(), &[]).unwrap();
+        let (_, edges) = extract_page(html, "/", &options(&friends())).unwrap();
 
         assert_eq!(edges.len(), 1);
         assert_eq!(edges[0].target, "/about/");
@@ -496,7 +1059,7 @@ mod tests {
             <body><a href="../about/index.html">About</a></body></html>
         "#;
 
-        let (_, edges) = extract_page(html, "/posts/hello", BASE_URL, &friends(), &[]).unwrap();
+        let (_, edges) = extract_page(html, "/posts/hello", &options(&friends())).unwrap();
 
         assert_eq!(edges.len(), 1);
         assert_eq!(edges[0].target, "/about/");
@@ -510,17 +1073,47 @@ mod tests {
             <body><a href="?x=1">Query only</a></body></html>
         "#;
 
-        let (_, edges) = extract_page(html, "/", BASE_URL, &friends(), &[]).unwrap();
+        let (_, edges) = extract_page(html, "/", &options(&friends())).unwrap();
 
         assert!(edges.is_empty());
     }
 
+    #[test]
+    fn matching_selectors_reports_each_selector() {
+        let html = r#"
+            <html><body>
+                <article class="post"><p>Hello</p></article>
+            </body></html>
+        "#;
+        let selectors = vec![
+            String::from("article"),
+            String::from(".tag-list"),
+            String::from("article.post p"),
+        ];
+
+        let matched = matching_selectors(html, &selectors).unwrap();
+
+        assert_eq!(matched, vec![true, false, true]);
+    }
+
+    #[test]
+    fn matching_selectors_rejects_invalid_css_selector() {
+        let result = matching_selectors("<html></html>", &[String::from("a]")]);
+
+        assert!(matches!(result, Err(Error::HtmlParse(_))));
+    }
+
     #[test]
     fn extract_rejects_invalid_css_selector() {
         let html = "<html><body><a href=\"/about\">link</a></body></html>";
         let selectors = vec![String::from("a]")];
 
-        let result = extract_page(html, "/", BASE_URL, &friends(), &selectors);
+        let friends = friends();
+        let options = ExtractOptions {
+            exclude_selectors: &selectors,
+            ..options(&friends)
+        };
+        let result = extract_page(html, "/", &options);
 
         assert!(result.is_err());
         let err = result.unwrap_err();
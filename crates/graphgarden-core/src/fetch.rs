@@ -0,0 +1,185 @@
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use ureq::{Agent, Body};
+use url::Url;
+
+use crate::error::{Error, Result};
+use crate::model::{Friend, PublicFile, Shard};
+use crate::signature::{SIGNATURE_EXTENSION, verify_public_file};
+
+pub use crate::output::WELL_KNOWN_PATH;
+
+/// Longest a request may take in total, so one slow friend never hangs a command.
+pub const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Largest response body read, in bytes: 256 MiB.
+///
+/// Well above the largest public files and shards, while bounding the memory a
+/// misbehaving origin can make a command use.
+pub const MAX_BODY_SIZE: u64 = 256 * 1024 * 1024;
+
+/// HTTP agent shared by every request, reusing its connections.
+pub(crate) static AGENT: LazyLock<Agent> = LazyLock::new(|| {
+    Agent::config_builder()
+        .timeout_global(Some(FETCH_TIMEOUT))
+        .build()
+        .into()
+});
+
+/// Resolves the URL of a public file from either its own URL or a site URL.
+///
+/// A URL whose path ends with `/` (e.g. `https://bob.dev/`) is treated as a
+/// site base URL and points to its `/.well-known/graphgarden.json`.
+pub fn public_file_url(input: &str) -> Result<Url> {
+    let url = Url::parse(input)
+        .map_err(|error| Error::InvalidFetchUrl(input.to_owned(), error.to_string()))?;
+
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(Error::InvalidFetchUrl(
+            input.to_owned(),
+            String::from("must use http or https"),
+        ));
+    }
+
+    if url.path().ends_with('/') {
+        return url
+            .join(WELL_KNOWN_PATH)
+            .map_err(|error| Error::InvalidFetchUrl(input.to_owned(), error.to_string()));
+    }
+
+    Ok(url)
+}
+
+/// Downloads and parses a public file, see [`public_file_url`] for accepted URLs.
+///
+/// A sharded file is reassembled from its shards, fetched relative to the index URL.
+pub fn fetch_public_file(input: &str) -> Result<PublicFile> {
+    public_file_from(&Network, input)
+}
+
+/// Downloads the detached signature of a public file, at its URL followed by `.sig`.
+pub fn fetch_signature(input: &str) -> Result<String> {
+    signature_from(&Network, input)
+}
+
+/// Downloads a friend's public file and verifies its signature.
+///
+/// The signature must match the friend's pinned [`Friend::public_key`] when
+/// set, and the key declared in the file otherwise.
+pub fn fetch_verified_public_file(friend: &Friend) -> Result<PublicFile> {
+    verified_public_file_from(&Network, friend)
+}
+
+/// Where the files are downloaded from: the network, or a [`FriendCache`](crate::cache::FriendCache).
+pub(crate) trait Get {
+    fn get(&self, url: &Url) -> Result<String>;
+}
+
+struct Network;
+
+impl Get for Network {
+    fn get(&self, url: &Url) -> Result<String> {
+        AGENT
+            .get(url.as_str())
+            .call()
+            .map_err(|error| error.to_string())
+            .and_then(|mut response| read_body(response.body_mut(), MAX_BODY_SIZE))
+            .map_err(|error| Error::Fetch(url.to_string(), error))
+    }
+}
+
+/// Reads a response body as text, failing clearly once it is larger than `limit` bytes.
+pub(crate) fn read_body(body: &mut Body, limit: u64) -> std::result::Result<String, String> {
+    body.with_config()
+        .limit(limit)
+        .read_to_string()
+        .map_err(|error| match error {
+            ureq::Error::BodyExceedsLimit(_) => {
+                format!("the response body is larger than {limit} bytes")
+            }
+            error => error.to_string(),
+        })
+}
+
+pub(crate) fn public_file_from(source: &impl Get, input: &str) -> Result<PublicFile> {
+    let url = public_file_url(input)?;
+    let index = PublicFile::from_json(&source.get(&url)?)?;
+
+    let shards = index
+        .shards
+        .iter()
+        .map(|shard_ref| {
+            let shard_url = url.join(shard_ref.checked_path()?).map_err(|error| {
+                Error::InvalidFetchUrl(shard_ref.path.clone(), error.to_string())
+            })?;
+            Ok(Shard::from_json(&source.get(&shard_url)?)?)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(index.reassemble(shards)?)
+}
+
+pub(crate) fn signature_from(source: &impl Get, input: &str) -> Result<String> {
+    let url = public_file_url(input)?;
+    let signature_url = Url::parse(&format!("{url}.{SIGNATURE_EXTENSION}"))
+        .map_err(|error| Error::InvalidFetchUrl(input.to_owned(), error.to_string()))?;
+    source.get(&signature_url)
+}
+
+pub(crate) fn verified_public_file_from(source: &impl Get, friend: &Friend) -> Result<PublicFile> {
+    let location = friend.public_file.as_deref().unwrap_or(&friend.url);
+    let file = public_file_from(source, location)?;
+    let signature = signature_from(source, location)?;
+    verify_public_file(&file, &signature, friend.public_key.as_deref())?;
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_time_out() {
+        assert_eq!(AGENT.config().timeouts().global, Some(FETCH_TIMEOUT));
+    }
+
+    #[test]
+    fn bodies_are_read_up_to_the_limit() {
+        let mut body = Body::builder().data("0123456789");
+        assert_eq!(read_body(&mut body, 100).unwrap(), "0123456789");
+
+        let mut body = Body::builder().data("0123456789");
+        assert_eq!(
+            read_body(&mut body, 5).unwrap_err(),
+            "the response body is larger than 5 bytes"
+        );
+    }
+
+    #[test]
+    fn site_url_resolves_to_well_known_file() {
+        let url = public_file_url("https://bob.dev/").unwrap();
+        assert_eq!(url.as_str(), "https://bob.dev/.well-known/graphgarden.json");
+
+        let url = public_file_url("https://bob.dev/garden/").unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://bob.dev/garden/.well-known/graphgarden.json"
+        );
+    }
+
+    #[test]
+    fn file_url_is_kept_as_is() {
+        let url = public_file_url("https://bob.dev/.well-known/graphgarden.json").unwrap();
+        assert_eq!(url.as_str(), "https://bob.dev/.well-known/graphgarden.json");
+    }
+
+    #[test]
+    fn rejects_non_http_urls() {
+        let result = public_file_url("ftp://bob.dev/");
+        assert!(matches!(result, Err(Error::InvalidFetchUrl(_, _))));
+
+        let result = public_file_url("bob.dev");
+        assert!(matches!(result, Err(Error::InvalidFetchUrl(_, _))));
+    }
+}
@@ -0,0 +1,233 @@
+use std::path::Path;
+
+use crate::config::validate_base_url;
+use crate::error::Result;
+use crate::preset::Preset;
+
+/// Detects the generator of the project at `dir`, from its configuration files.
+///
+/// Zola and Hugo both use `config.toml`; Zola is told apart by its
+/// `base_url` key, Hugo using `baseURL`.
+pub fn detect(dir: &Path) -> Option<Preset> {
+    let exists = |name: &str| dir.join(name).exists();
+    let any = |names: &[&str]| names.iter().any(|name| exists(name));
+
+    if exists("book.toml") {
+        return Some(Preset::MdBook);
+    }
+    if exists("zola.toml") {
+        return Some(Preset::Zola);
+    }
+    if let Ok(config) = std::fs::read_to_string(dir.join("config.toml")) {
+        if config
+            .lines()
+            .any(|line| line.trim_start().starts_with("base_url"))
+        {
+            return Some(Preset::Zola);
+        }
+        if config.contains("baseURL") || exists("archetypes") {
+            return Some(Preset::Hugo);
+        }
+    }
+    if any(&["hugo.toml", "hugo.yaml", "hugo.json"]) {
+        return Some(Preset::Hugo);
+    }
+    if any(&["_config.yml", "_config.yaml"]) {
+        return Some(Preset::Jekyll);
+    }
+    if any(&[
+        "eleventy.config.js",
+        "eleventy.config.mjs",
+        "eleventy.config.cjs",
+        ".eleventy.js",
+    ]) {
+        return Some(Preset::Eleventy);
+    }
+    if any(&[
+        "astro.config.mjs",
+        "astro.config.js",
+        "astro.config.ts",
+        "astro.config.mts",
+    ]) {
+        return Some(Preset::Astro);
+    }
+    if any(&["next.config.js", "next.config.mjs", "next.config.ts"]) {
+        return Some(Preset::Next);
+    }
+    None
+}
+
+/// Renders a commented `graphgarden.toml` for a project, with the preset of
+/// its generator when detected, and generic `[parse]` settings otherwise.
+///
+/// `project_dir` is the project as seen from where `graphgarden` runs, since
+/// `output.dir` is resolved against the working directory, not the config file.
+///
+/// Fails when `base_url` is not an absolute HTTP(S) URL ending with `/`.
+pub fn render_config(
+    preset: Option<Preset>,
+    project_dir: &Path,
+    base_url: &str,
+    title: &str,
+) -> Result<String> {
+    validate_base_url(base_url)?;
+
+    let (header, output_dir, parse) = match preset {
+        Some(preset) => (
+            format!("# GraphGarden configuration for a {} site.", preset.name()),
+            preset.output_dir(),
+            format!(
+                r#"# Expands to the include and exclude globs, exclude_selectors and page extensions
+# of the generator, see `graphgarden explain`
+preset = {}
+# exclude = ["drafts/**"]            # extends the globs of the preset
+# exclude_selectors = [".related"]   # replaces the selectors of the preset
+"#,
+                toml_string(preset.key())
+            ),
+        ),
+        None => (
+            String::from("# GraphGarden configuration."),
+            "dist",
+            String::from(
+                r#"include = ["**/*.html"]
+exclude = ["404.html"]
+# CSS selectors to skip when extracting links, such as the site-wide navigation
+exclude_selectors = ["header", "footer", "nav"]
+"#,
+            ),
+        ),
+    };
+
+    Ok(format!(
+        r#"{header}
+# See https://github.com/bruits/graphgarden/tree/main/crates/graphgarden#configuration
+
+# Friends are bare URLs, or tables with details, e.g.
+# {{ url = "https://bob.dev/", name = "Bob" }}
+friends = []
+
+[site]
+base_url = {}
+title    = {}
+# description = "A blog about …"
+# language    = "en"
+
+[output]
+dir = {}    # where the site is built, run `graphgarden build` after it
+
+[parse]
+{parse}"#,
+        toml_string(base_url),
+        toml_string(title),
+        toml_string(&project_dir.join(output_dir).to_string_lossy()),
+    ))
+}
+
+fn toml_string(value: &str) -> String {
+    toml::Value::String(value.to_owned()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::error::Error;
+    use tempfile::TempDir;
+
+    fn project_with(files: &[(&str, &str)]) -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        for (name, content) in files {
+            std::fs::write(tmp.path().join(name), content).unwrap();
+        }
+        tmp
+    }
+
+    #[test]
+    fn detects_generators_from_their_config_files() {
+        let cases = [
+            ("astro.config.mjs", "", Preset::Astro),
+            (".eleventy.js", "", Preset::Eleventy),
+            ("hugo.toml", "", Preset::Hugo),
+            (
+                "config.toml",
+                "baseURL = 'https://alice.dev/'",
+                Preset::Hugo,
+            ),
+            (
+                "config.toml",
+                "base_url = \"https://alice.dev\"",
+                Preset::Zola,
+            ),
+            ("_config.yml", "", Preset::Jekyll),
+            ("book.toml", "", Preset::MdBook),
+            ("next.config.js", "", Preset::Next),
+        ];
+        for (name, content, expected) in cases {
+            let tmp = project_with(&[(name, content)]);
+            assert_eq!(detect(tmp.path()), Some(expected), "{name}");
+        }
+
+        let tmp = project_with(&[("package.json", "{}")]);
+        assert_eq!(detect(tmp.path()), None);
+    }
+
+    #[test]
+    fn rendered_config_parses_with_the_preset() {
+        let toml = render_config(
+            Some(Preset::MdBook),
+            Path::new(""),
+            "https://docs.dev/",
+            "The \"Docs\"",
+        )
+        .unwrap();
+
+        let config: Config = toml.parse().unwrap();
+        assert_eq!(config.site.base_url, "https://docs.dev/");
+        assert_eq!(config.site.title, "The \"Docs\"");
+        assert_eq!(config.output.dir, "book");
+        assert_eq!(config.parse.preset, Some(Preset::MdBook));
+        assert_eq!(
+            config.parse.effective().exclude,
+            vec!["404.html", "print.html", "toc.html"]
+        );
+        assert!(toml.starts_with("# GraphGarden configuration for a mdBook site."));
+    }
+
+    #[test]
+    fn rendered_config_falls_back_to_generic_defaults() {
+        let config: Config = render_config(None, Path::new(""), "https://alice.dev/", "Alice")
+            .unwrap()
+            .parse()
+            .unwrap();
+
+        assert_eq!(config.output.dir, "dist");
+        assert_eq!(config.parse.preset, None);
+        assert_eq!(config.parse.include, Some(vec![String::from("**/*.html")]));
+        assert_eq!(config.parse.exclude, Some(vec![String::from("404.html")]));
+    }
+
+    #[test]
+    fn rendered_output_dir_is_relative_to_the_working_directory() {
+        let config: Config = render_config(
+            Some(Preset::Hugo),
+            Path::new("site"),
+            "https://alice.dev/",
+            "Alice",
+        )
+        .unwrap()
+        .parse()
+        .unwrap();
+
+        assert_eq!(
+            Path::new(&config.output.dir),
+            Path::new("site").join("public")
+        );
+    }
+
+    #[test]
+    fn render_config_rejects_invalid_base_url() {
+        let result = render_config(None, Path::new(""), "https://alice.dev", "Alice");
+        assert!(matches!(result, Err(Error::InvalidBaseUrl(_, _))));
+    }
+}
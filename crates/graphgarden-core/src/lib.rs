@@ -4,10 +4,23 @@
 //! Core library for crawling, graph model, and link extraction.
 
 pub mod build;
+#[cfg(feature = "fetch")]
+pub mod cache;
 pub mod config;
 pub mod error;
 pub mod extract;
+#[cfg(feature = "fetch")]
+pub mod fetch;
+pub mod init;
+pub mod merge;
 pub mod model;
+pub mod output;
+pub mod preset;
+pub mod ring;
+#[cfg(feature = "serve")]
+pub mod serve;
+#[cfg(feature = "sign")]
+pub mod signature;
 
 pub use error::{Error, Result};
 pub use model::PROTOCOL_VERSION;
@@ -0,0 +1,240 @@
+use std::collections::HashSet;
+
+use jiff::Timestamp;
+use url::Url;
+
+use crate::build::format_timestamp;
+use crate::error::{Error, Result};
+use crate::model::{MergedEdge, MergedGraph, MergedNode, MergedSite, PROTOCOL_VERSION, PublicFile};
+
+/// Default location of the merged graph, relative to the output directory.
+pub const MERGED_PATH: &str = "graphgarden-merged.json";
+
+/// Stitches the graph of a site together with the graphs of its friends.
+///
+/// URLs are resolved against the `base_url` of the file declaring them, as the
+/// web component does, so the pages of every site share one namespace. A page
+/// declared by several files keeps the first declaration, the site's own
+/// first, and the same link is only kept once: links between the same pages
+/// are kept apart when their type or `context` differ, e.g. a link from both
+/// `nav` and `main`. Files of a site already merged are skipped.
+pub fn merge(
+    own: &PublicFile,
+    friends: &[PublicFile],
+    generated_at: Timestamp,
+) -> Result<MergedGraph> {
+    let mut graph = MergedGraph {
+        version: PROTOCOL_VERSION.to_owned(),
+        generated_at: format_timestamp(generated_at),
+        base_url: own.base_url.clone(),
+        sites: Vec::new(),
+        nodes: Vec::new(),
+        edges: Vec::new(),
+    };
+    let mut node_urls = HashSet::new();
+    let mut edge_keys = HashSet::new();
+
+    for file in std::iter::once(own).chain(friends) {
+        let base_url = Url::parse(&file.base_url)
+            .map_err(|error| Error::InvalidBaseUrl(file.base_url.clone(), error.to_string()))?;
+        if graph
+            .sites
+            .iter()
+            .any(|site| site.base_url == base_url.as_str())
+        {
+            continue;
+        }
+        let absolute = |url: &str| {
+            base_url
+                .join(url)
+                .map(String::from)
+                .map_err(|error| Error::InvalidMergeUrl(url.to_owned(), error.to_string()))
+        };
+
+        for node in &file.nodes {
+            let url = absolute(&node.url)?;
+            if node_urls.insert(url.clone()) {
+                graph.nodes.push(MergedNode {
+                    url,
+                    title: node.title.clone(),
+                    group: node.group.clone(),
+                    site: base_url.to_string(),
+                });
+            }
+        }
+
+        for edge in &file.edges {
+            let edge = MergedEdge {
+                source: absolute(&edge.source)?,
+                target: absolute(&edge.target)?,
+                edge_type: edge.edge_type.clone(),
+                context: edge.context.clone(),
+            };
+            let key = (
+                edge.source.clone(),
+                edge.target.clone(),
+                edge.edge_type.clone(),
+                edge.context.clone(),
+            );
+            if edge_keys.insert(key) {
+                graph.edges.push(edge);
+            }
+        }
+
+        graph.sites.push(MergedSite {
+            base_url: base_url.to_string(),
+            site: file.site.clone(),
+        });
+    }
+
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(
+        base_url: &str,
+        title: &str,
+        nodes: &[(&str, &str)],
+        edges: &[(&str, &str, &str)],
+    ) -> PublicFile {
+        let nodes: Vec<_> = nodes
+            .iter()
+            .map(|(url, title)| serde_json::json!({ "url": url, "title": title }))
+            .collect();
+        let edges: Vec<_> = edges
+            .iter()
+            .map(|(source, target, edge_type)| {
+                serde_json::json!({ "source": source, "target": target, "type": edge_type })
+            })
+            .collect();
+        let json = serde_json::json!({
+            "version": "0.2.0",
+            "generated_at": "2026-02-17T12:00:00Z",
+            "base_url": base_url,
+            "site": { "title": title },
+            "friends": [],
+            "nodes": nodes,
+            "edges": edges,
+        });
+        PublicFile::from_json(&json.to_string()).unwrap()
+    }
+
+    #[test]
+    fn absolutizes_and_deduplicates_the_graphs() {
+        let alice = file(
+            "https://alice.dev/",
+            "Alice",
+            &[("/", "Home"), ("/posts/hello", "Hello")],
+            &[
+                ("/", "/posts/hello", "internal"),
+                ("/posts/hello", "https://bob.dev/", "friend"),
+            ],
+        );
+        let bob = file(
+            "https://bob.dev/",
+            "Bob",
+            &[("/", "Bob's home")],
+            &[
+                ("/", "https://alice.dev/posts/hello", "friend"),
+                ("/", "https://alice.dev/posts/hello", "friend"),
+                ("/", "/missing", "internal"),
+            ],
+        );
+        // Declares a page of Alice, and is listed twice
+        let carol = file(
+            "https://carol.dev/",
+            "Carol",
+            &[("https://alice.dev/", "Not Alice's title")],
+            &[],
+        );
+
+        let timestamp: Timestamp = "2026-03-01T00:00:00Z".parse().unwrap();
+        let graph = merge(&alice, &[bob, carol.clone(), carol], timestamp).unwrap();
+
+        assert_eq!(graph.base_url, "https://alice.dev/");
+        assert_eq!(graph.generated_at, "2026-03-01T00:00:00Z");
+        let sites: Vec<_> = graph
+            .sites
+            .iter()
+            .map(|site| site.base_url.as_str())
+            .collect();
+        assert_eq!(
+            sites,
+            [
+                "https://alice.dev/",
+                "https://bob.dev/",
+                "https://carol.dev/"
+            ]
+        );
+
+        let nodes: Vec<_> = graph
+            .nodes
+            .iter()
+            .map(|node| (node.url.as_str(), node.title.as_str(), node.site.as_str()))
+            .collect();
+        assert_eq!(
+            nodes,
+            [
+                ("https://alice.dev/", "Home", "https://alice.dev/"),
+                (
+                    "https://alice.dev/posts/hello",
+                    "Hello",
+                    "https://alice.dev/"
+                ),
+                ("https://bob.dev/", "Bob's home", "https://bob.dev/"),
+            ]
+        );
+
+        let edges: Vec<_> = graph
+            .edges
+            .iter()
+            .map(|edge| (edge.source.as_str(), edge.target.as_str()))
+            .collect();
+        assert_eq!(
+            edges,
+            [
+                ("https://alice.dev/", "https://alice.dev/posts/hello"),
+                ("https://alice.dev/posts/hello", "https://bob.dev/"),
+                ("https://bob.dev/", "https://alice.dev/posts/hello"),
+                ("https://bob.dev/", "https://bob.dev/missing"),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_the_same_link_from_different_contexts() {
+        let mut alice = file(
+            "https://alice.dev/",
+            "Alice",
+            &[("/", "Home"), ("/about", "About")],
+            &[
+                ("/", "/about", "internal"),
+                ("/", "/about", "internal"),
+                ("/", "/about", "internal"),
+            ],
+        );
+        alice.edges[0].context = Some(String::from("nav"));
+        alice.edges[1].context = Some(String::from("main"));
+        alice.edges[2].context = Some(String::from("nav"));
+
+        let graph = merge(&alice, &[], Timestamp::UNIX_EPOCH).unwrap();
+
+        let contexts: Vec<_> = graph
+            .edges
+            .iter()
+            .map(|edge| edge.context.as_deref())
+            .collect();
+        assert_eq!(contexts, [Some("nav"), Some("main")]);
+    }
+
+    #[test]
+    fn rejects_an_invalid_base_url() {
+        let alice = file("https://alice.dev/", "Alice", &[], &[]);
+        let broken = file("not a url", "Broken", &[], &[]);
+        let result = merge(&alice, &[broken], Timestamp::UNIX_EPOCH);
+        assert!(matches!(result, Err(Error::InvalidBaseUrl(_, _))));
+    }
+}
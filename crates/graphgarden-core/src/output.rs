@@ -0,0 +1,556 @@
+use std::fs;
+#[cfg(feature = "compress")]
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "compress")]
+use flate2::Compression;
+#[cfg(feature = "compress")]
+use flate2::write::GzEncoder;
+
+use crate::config::OutputConfig;
+use crate::error::{Error, Result};
+use crate::model::{ChangeFeed, PublicFile, SHARD_DIR, Shard, ShardRef};
+
+/// Well-known path of the public file, relative to a site's base URL.
+pub const WELL_KNOWN_PATH: &str = ".well-known/graphgarden.json";
+
+/// Extension of the detached signature, e.g. `graphgarden.json.sig`.
+pub const SIGNATURE_EXTENSION: &str = "sig";
+
+/// Highest gzip compression level.
+pub const MAX_GZIP_LEVEL: u32 = 9;
+/// Highest brotli compression level (quality).
+pub const MAX_BROTLI_LEVEL: u32 = 11;
+
+/// Path of the public file under an output directory.
+pub fn public_file_path(output_dir: &Path) -> PathBuf {
+    output_dir.join(".well-known").join("graphgarden.json")
+}
+
+/// Writes the public file under `output.dir`, with its `.gz` and `.br` siblings when enabled.
+///
+/// Every file is first written to a temporary sibling, then all are renamed
+/// into place, so readers never see a partial file. Siblings of a disabled
+/// compression are removed once the new files are in place, so they never go
+/// stale. Returns the path of the JSON file.
+pub fn write_public_file(output: &OutputConfig, json: &str) -> Result<PathBuf> {
+    write_sharded_public_file(output, json, &[], None)
+}
+
+/// Writes a sharded public file: the index, and each shard at its [`ShardRef::path`].
+///
+/// Shards are compressed like the index, and renamed into place before it, so
+/// the index never points at a missing shard. Shard files left over from a
+/// previous build are only removed once the new index is in place. A
+/// `signature` is written to the `graphgarden.json.sig` sibling, which is
+/// removed otherwise. See [`write_public_file`].
+pub fn write_sharded_public_file(
+    output: &OutputConfig,
+    index_json: &str,
+    shards: &[(ShardRef, String)],
+    signature: Option<&str>,
+) -> Result<PathBuf> {
+    let layout = Layout::new(output, index_json, shards, signature)?;
+    let well_known = layout
+        .destination
+        .parent()
+        .expect("the public file path has a parent");
+    fs::create_dir_all(well_known).map_err(|e| Error::FileWrite(e, well_known.to_path_buf()))?;
+    if !shards.is_empty() {
+        fs::create_dir_all(&layout.shard_dir)
+            .map_err(|e| Error::FileWrite(e, layout.shard_dir.clone()))?;
+    }
+
+    let written: Vec<PathBuf> = layout.files.iter().map(|(path, _)| path.clone()).collect();
+    stage_and_rename(layout.files)?;
+    for path in layout.stale {
+        remove_if_exists(&path)?;
+    }
+    remove_stale_shards(&layout.shard_dir, &written)?;
+    Ok(layout.destination)
+}
+
+/// Whether [`write_sharded_public_file`] would leave the output exactly as it is.
+///
+/// Compares the bytes of every file it would write, compressed siblings and
+/// signature included, and checks that nothing it would remove is there.
+pub fn is_written(
+    output: &OutputConfig,
+    index_json: &str,
+    shards: &[(ShardRef, String)],
+    signature: Option<&str>,
+) -> Result<bool> {
+    let layout = Layout::new(output, index_json, shards, signature)?;
+
+    let files_match = layout
+        .files
+        .iter()
+        .all(|(path, content)| fs::read(path).is_ok_and(|existing| existing == *content));
+    let nothing_stale = layout.stale.iter().all(|path| !path.exists());
+    let no_stale_shards = match fs::read_dir(&layout.shard_dir) {
+        Ok(entries) => entries.filter_map(|entry| entry.ok()).all(|entry| {
+            let path = entry.path();
+            !path.is_file() || layout.files.iter().any(|(file, _)| *file == path)
+        }),
+        Err(_) => true,
+    };
+
+    Ok(files_match && nothing_stale && no_stale_shards)
+}
+
+/// The files of a public file write, in the order they are renamed into place.
+struct Layout {
+    destination: PathBuf,
+    shard_dir: PathBuf,
+    /// Shards first and the index last, so the index never points at a missing shard.
+    files: Vec<(PathBuf, Vec<u8>)>,
+    /// Siblings of a disabled compression, and the signature when unsigned.
+    stale: Vec<PathBuf>,
+}
+
+impl Layout {
+    fn new(
+        output: &OutputConfig,
+        index_json: &str,
+        shards: &[(ShardRef, String)],
+        signature: Option<&str>,
+    ) -> Result<Self> {
+        let destination = public_file_path(Path::new(&output.dir));
+        let well_known = destination
+            .parent()
+            .expect("the public file path has a parent");
+        let shard_dir = well_known.join(SHARD_DIR);
+
+        let mut files = Vec::new();
+        let mut stale = Vec::new();
+        for (shard_ref, json) in shards {
+            let path = well_known.join(&shard_ref.path);
+            push_with_siblings(&mut files, &mut stale, output, path, json)?;
+        }
+        let signature_path = sibling(&destination, SIGNATURE_EXTENSION);
+        match signature {
+            Some(signature) => {
+                files.push((signature_path, format!("{signature}\n").into_bytes()));
+            }
+            None => stale.push(signature_path),
+        }
+        push_with_siblings(
+            &mut files,
+            &mut stale,
+            output,
+            destination.clone(),
+            index_json,
+        )?;
+
+        Ok(Self {
+            destination,
+            shard_dir,
+            files,
+            stale,
+        })
+    }
+}
+
+/// Path of the change feed under an output directory.
+pub fn change_feed_path(output_dir: &Path) -> PathBuf {
+    output_dir
+        .join(".well-known")
+        .join("graphgarden-changes.json")
+}
+
+/// Reads the change feed at `path`, or an empty feed when there is none yet.
+pub fn read_change_feed(path: &Path) -> Result<ChangeFeed> {
+    match fs::read_to_string(path) {
+        Ok(json) => Ok(ChangeFeed::from_json(&json)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ChangeFeed::default()),
+        Err(e) => Err(Error::FileRead(e, path.to_path_buf())),
+    }
+}
+
+/// Writes the change feed under `output.dir`, compressed like the public file.
+pub fn write_change_feed(output: &OutputConfig, json: &str) -> Result<PathBuf> {
+    let destination = change_feed_path(Path::new(&output.dir));
+    let well_known = destination
+        .parent()
+        .expect("the change feed path has a parent");
+    fs::create_dir_all(well_known).map_err(|e| Error::FileWrite(e, well_known.to_path_buf()))?;
+
+    let mut files = Vec::new();
+    let mut stale = Vec::new();
+    push_with_siblings(&mut files, &mut stale, output, destination.clone(), json)?;
+    stage_and_rename(files)?;
+    for path in stale {
+        remove_if_exists(&path)?;
+    }
+    Ok(destination)
+}
+
+/// Removes the change feed and its siblings, once the feed is disabled.
+pub fn remove_change_feed(output_dir: &Path) -> Result<()> {
+    let path = change_feed_path(output_dir);
+    remove_if_exists(&sibling(&path, "gz"))?;
+    remove_if_exists(&sibling(&path, "br"))?;
+    remove_if_exists(&path)
+}
+
+/// Writes every file to a temporary sibling, then renames them all into place, in order.
+///
+/// On failure, the temporary files not yet renamed are removed.
+fn stage_and_rename(files: Vec<(PathBuf, Vec<u8>)>) -> Result<()> {
+    let mut staged = Vec::with_capacity(files.len());
+    for (path, content) in files {
+        let temporary = sibling(&path, "tmp");
+        if let Err(e) = fs::write(&temporary, content) {
+            staged.push((temporary.clone(), path));
+            discard_staged(&staged);
+            return Err(Error::FileWrite(e, temporary));
+        }
+        staged.push((temporary, path));
+    }
+    for (i, (temporary, path)) in staged.iter().enumerate() {
+        if let Err(e) = fs::rename(temporary, path) {
+            discard_staged(&staged[i..]);
+            return Err(Error::FileWrite(e, path.clone()));
+        }
+    }
+    Ok(())
+}
+
+/// Best-effort removal of staged temporary files, after a failed write.
+fn discard_staged(staged: &[(PathBuf, PathBuf)]) {
+    for (temporary, _) in staged {
+        let _ = fs::remove_file(temporary);
+    }
+}
+
+/// Queues a JSON file along with its enabled compressed siblings, and the disabled ones for removal.
+fn push_with_siblings(
+    files: &mut Vec<(PathBuf, Vec<u8>)>,
+    stale: &mut Vec<PathBuf>,
+    output: &OutputConfig,
+    path: PathBuf,
+    json: &str,
+) -> Result<()> {
+    let gzip_path = sibling(&path, "gz");
+    let brotli_path = sibling(&path, "br");
+
+    match output.gzip {
+        Some(level) => files.push((gzip_path, gzip(json.as_bytes(), level)?)),
+        None => stale.push(gzip_path),
+    }
+    match output.brotli {
+        Some(level) => files.push((brotli_path, brotli(json.as_bytes(), level)?)),
+        None => stale.push(brotli_path),
+    }
+    files.push((path, json.as_bytes().to_vec()));
+
+    Ok(())
+}
+
+/// Removes the files of `shard_dir` that were not just written.
+fn remove_stale_shards(shard_dir: &Path, written: &[PathBuf]) -> Result<()> {
+    let entries = match fs::read_dir(shard_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(Error::FileRead(e, shard_dir.to_path_buf())),
+    };
+
+    for entry in entries {
+        let path = entry
+            .map_err(|e| Error::FileRead(e, shard_dir.to_path_buf()))?
+            .path();
+        if path.is_file() && !written.contains(&path) {
+            remove_if_exists(&path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads a public file, reassembling its shards when it is sharded.
+pub fn read_public_file(path: &Path) -> Result<PublicFile> {
+    let (index, shards) = read_sharded_public_file(path)?;
+    Ok(index.reassemble(shards)?)
+}
+
+/// Reads a public file as laid out on disk: the index, and its shards if any.
+///
+/// Shard paths are resolved relative to the directory of `path`, and must stay
+/// under its shard directory.
+pub fn read_sharded_public_file(path: &Path) -> Result<(PublicFile, Vec<Shard>)> {
+    let index = PublicFile::from_json(&read(path)?)?;
+
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let shards = index
+        .shards
+        .iter()
+        .map(|shard_ref| {
+            let shard_path = dir.join(shard_ref.checked_path()?);
+            Ok(Shard::from_json(&read(&shard_path)?)?)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok((index, shards))
+}
+
+fn read(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|e| Error::FileRead(e, path.to_path_buf()))
+}
+
+/// Appends an extension to a path, e.g. `graphgarden.json` → `graphgarden.json.gz`.
+fn sibling(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(extension);
+    PathBuf::from(name)
+}
+
+fn remove_if_exists(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(Error::FileWrite(e, path.to_path_buf()))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(feature = "compress")]
+fn gzip(content: &[u8], level: u32) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::new(level));
+    encoder
+        .write_all(content)
+        .and_then(|()| encoder.finish())
+        .map_err(Error::Compress)
+}
+
+#[cfg(feature = "compress")]
+fn brotli(content: &[u8], level: u32) -> Result<Vec<u8>> {
+    // Unlike dropping a `CompressorWriter`, this reports failures to finish the stream
+    let params = brotli::enc::BrotliEncoderParams {
+        quality: level as i32,
+        lgwin: 22,
+        ..Default::default()
+    };
+    let mut compressed = Vec::new();
+    brotli::BrotliCompress(&mut &content[..], &mut compressed, &params).map_err(Error::Compress)?;
+    Ok(compressed)
+}
+
+#[cfg(not(feature = "compress"))]
+fn gzip(_content: &[u8], _level: u32) -> Result<Vec<u8>> {
+    Err(Error::CompressionUnavailable("gzip"))
+}
+
+#[cfg(not(feature = "compress"))]
+fn brotli(_content: &[u8], _level: u32) -> Result<Vec<u8>> {
+    Err(Error::CompressionUnavailable("brotli"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "compress")]
+    use std::io::Read;
+
+    #[cfg(feature = "compress")]
+    use flate2::read::GzDecoder;
+    use tempfile::TempDir;
+
+    use crate::model::{Extensions, Node, ShardBy, SiteMetadata};
+
+    const JSON: &str = r#"{"version":"0.2.0","nodes":[]}"#;
+
+    fn output_config(dir: &Path, gzip: Option<u32>, brotli: Option<u32>) -> OutputConfig {
+        OutputConfig {
+            dir: dir.to_string_lossy().into_owned(),
+            gzip,
+            brotli,
+            ..OutputConfig::default()
+        }
+    }
+
+    #[test]
+    fn writes_only_json_by_default() {
+        let tmp = TempDir::new().unwrap();
+        let path = write_public_file(&output_config(tmp.path(), None, None), JSON).unwrap();
+
+        assert_eq!(path, tmp.path().join(".well-known/graphgarden.json"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), JSON);
+        assert!(!sibling(&path, "gz").exists());
+        assert!(!sibling(&path, "br").exists());
+        assert!(!sibling(&path, "tmp").exists());
+    }
+
+    #[test]
+    #[cfg(feature = "compress")]
+    fn writes_decodable_compressed_siblings() {
+        let tmp = TempDir::new().unwrap();
+        let path = write_public_file(&output_config(tmp.path(), Some(9), Some(11)), JSON).unwrap();
+
+        let mut gunzipped = String::new();
+        GzDecoder::new(fs::File::open(sibling(&path, "gz")).unwrap())
+            .read_to_string(&mut gunzipped)
+            .unwrap();
+        assert_eq!(gunzipped, JSON);
+
+        let mut unbrotlied = String::new();
+        brotli::Decompressor::new(fs::File::open(sibling(&path, "br")).unwrap(), 4096)
+            .read_to_string(&mut unbrotlied)
+            .unwrap();
+        assert_eq!(unbrotlied, JSON);
+    }
+
+    #[test]
+    #[cfg(feature = "compress")]
+    fn removes_siblings_once_disabled() {
+        let tmp = TempDir::new().unwrap();
+        let path = write_public_file(&output_config(tmp.path(), Some(6), Some(5)), JSON).unwrap();
+        assert!(sibling(&path, "gz").exists());
+
+        write_public_file(&output_config(tmp.path(), None, Some(5)), JSON).unwrap();
+        assert!(!sibling(&path, "gz").exists());
+        assert!(sibling(&path, "br").exists());
+    }
+
+    fn sharded(by: ShardBy) -> (PublicFile, Vec<(ShardRef, String)>) {
+        let node = |url: &str| Node {
+            url: String::from(url),
+            title: String::from("Page"),
+            group: None,
+            extensions: Extensions::new(),
+        };
+        let file = PublicFile {
+            version: String::from(crate::model::PROTOCOL_VERSION),
+            generated_at: String::from("2026-02-17T12:00:00Z"),
+            base_url: String::from("https://alice.dev/"),
+            site: SiteMetadata {
+                title: String::from("Alice's Garden"),
+                description: None,
+                language: None,
+                public_key: None,
+                extensions: Extensions::new(),
+            },
+            friends: Vec::new(),
+            nodes: vec![node("/"), node("/posts/hello"), node("/posts/world")],
+            edges: Vec::new(),
+            rings: Vec::new(),
+            shards: Vec::new(),
+            extensions: Extensions::new(),
+        };
+
+        let (index, shards) = file.into_shards(by);
+        let shards = index
+            .shards
+            .iter()
+            .cloned()
+            .zip(shards.iter().map(|shard| shard.to_json().unwrap()))
+            .collect();
+        (index, shards)
+    }
+
+    #[test]
+    #[cfg(feature = "compress")]
+    fn writes_and_reads_back_shards() {
+        let tmp = TempDir::new().unwrap();
+        let output = output_config(tmp.path(), Some(6), None);
+        let (index, shards) = sharded(ShardBy::Prefix);
+
+        let path =
+            write_sharded_public_file(&output, &index.to_json().unwrap(), &shards, None).unwrap();
+        assert!(tmp.path().join(".well-known/graphgarden/1.json").exists());
+        assert!(
+            tmp.path()
+                .join(".well-known/graphgarden/1.json.gz")
+                .exists()
+        );
+
+        let restored = read_public_file(&path).unwrap();
+        assert_eq!(restored.nodes.len(), 3);
+        assert!(restored.shards.is_empty());
+    }
+
+    #[test]
+    fn refuses_to_read_shards_outside_the_shard_directory() {
+        let tmp = TempDir::new().unwrap();
+        let output = output_config(tmp.path(), None, None);
+        let (mut index, shards) = sharded(ShardBy::Prefix);
+        index.shards[0].path = String::from("../../secret.json");
+
+        let path =
+            write_sharded_public_file(&output, &index.to_json().unwrap(), &shards, None).unwrap();
+        let result = read_public_file(&path);
+        assert!(matches!(
+            result,
+            Err(Error::Protocol(
+                graphgarden_protocol::Error::InvalidShardPath(_)
+            ))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "compress")]
+    fn change_feed_round_trips_and_is_removable() {
+        let tmp = TempDir::new().unwrap();
+        let output = output_config(tmp.path(), None, Some(5));
+        let path = change_feed_path(tmp.path());
+        assert_eq!(read_change_feed(&path).unwrap(), ChangeFeed::default());
+
+        let (previous, _) = sharded(ShardBy::Prefix);
+        let (mut current, _) = sharded(ShardBy::Prefix);
+        current.generated_at = String::from("2026-02-18T12:00:00Z");
+        let mut feed = ChangeFeed::default();
+        feed.push(current.diff(&previous), 5);
+        write_change_feed(&output, &feed.to_json().unwrap()).unwrap();
+
+        assert_eq!(read_change_feed(&path).unwrap(), feed);
+        assert!(sibling(&path, "br").exists());
+
+        remove_change_feed(tmp.path()).unwrap();
+        assert!(!path.exists());
+        assert!(!sibling(&path, "br").exists());
+    }
+
+    #[test]
+    fn removes_stale_shards() {
+        let tmp = TempDir::new().unwrap();
+        let output = output_config(tmp.path(), None, None);
+        let (index, shards) = sharded(ShardBy::Size(1));
+        write_sharded_public_file(&output, &index.to_json().unwrap(), &shards, None).unwrap();
+        assert!(tmp.path().join(".well-known/graphgarden/2.json").exists());
+
+        let (index, shards) = sharded(ShardBy::Size(2));
+        write_sharded_public_file(&output, &index.to_json().unwrap(), &shards, None).unwrap();
+        assert!(tmp.path().join(".well-known/graphgarden/1.json").exists());
+        assert!(!tmp.path().join(".well-known/graphgarden/2.json").exists());
+
+        write_public_file(&output, JSON).unwrap();
+        assert!(!tmp.path().join(".well-known/graphgarden/0.json").exists());
+    }
+
+    #[test]
+    fn failed_write_keeps_the_previous_index_and_its_shards() {
+        let tmp = TempDir::new().unwrap();
+        let output = output_config(tmp.path(), None, None);
+        let (index, shards) = sharded(ShardBy::Size(2));
+        let path =
+            write_sharded_public_file(&output, &index.to_json().unwrap(), &shards, None).unwrap();
+        let previous = fs::read_to_string(&path).unwrap();
+
+        // A directory where a new shard goes makes its rename fail.
+        fs::create_dir(tmp.path().join(".well-known/graphgarden/2.json")).unwrap();
+        let (index, shards) = sharded(ShardBy::Size(1));
+        let result = write_sharded_public_file(&output, &index.to_json().unwrap(), &shards, None);
+        assert!(matches!(result, Err(Error::FileWrite(..))));
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), previous);
+        assert!(tmp.path().join(".well-known/graphgarden/0.json").exists());
+        assert!(tmp.path().join(".well-known/graphgarden/1.json").exists());
+        let leftovers = walkdir::WalkDir::new(tmp.path())
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "tmp"))
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+}
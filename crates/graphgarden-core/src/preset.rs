@@ -0,0 +1,102 @@
+use serde::Deserialize;
+
+/// Output conventions of a static site generator, set with `[parse] preset`.
+///
+/// A preset gives the defaults of the `[parse]` section, see
+/// [`ParseConfig::effective`](crate::config::ParseConfig::effective).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+    Astro,
+    Eleventy,
+    Hugo,
+    Jekyll,
+    #[serde(rename = "mdbook")]
+    MdBook,
+    /// Next.js static export.
+    Next,
+    Zola,
+}
+
+impl Preset {
+    /// Every preset, in alphabetical order.
+    pub const ALL: [Preset; 7] = [
+        Preset::Astro,
+        Preset::Eleventy,
+        Preset::Hugo,
+        Preset::Jekyll,
+        Preset::MdBook,
+        Preset::Next,
+        Preset::Zola,
+    ];
+
+    /// Value of `[parse] preset`, e.g. `mdbook`.
+    pub fn key(self) -> &'static str {
+        match self {
+            Preset::Astro => "astro",
+            Preset::Eleventy => "eleventy",
+            Preset::Hugo => "hugo",
+            Preset::Jekyll => "jekyll",
+            Preset::MdBook => "mdbook",
+            Preset::Next => "next",
+            Preset::Zola => "zola",
+        }
+    }
+
+    /// Display name of the generator.
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Astro => "Astro",
+            Preset::Eleventy => "Eleventy",
+            Preset::Hugo => "Hugo",
+            Preset::Jekyll => "Jekyll",
+            Preset::MdBook => "mdBook",
+            Preset::Next => "Next.js (static export)",
+            Preset::Zola => "Zola",
+        }
+    }
+
+    /// Default directory the generator writes the built site to.
+    pub fn output_dir(self) -> &'static str {
+        match self {
+            Preset::Astro => "dist",
+            Preset::Eleventy | Preset::Jekyll => "_site",
+            Preset::Hugo | Preset::Zola => "public",
+            Preset::MdBook => "book",
+            Preset::Next => "out",
+        }
+    }
+
+    /// Globs of the pages to parse.
+    pub fn include(self) -> &'static [&'static str] {
+        match self {
+            Preset::Jekyll => &["**/*.html", "**/*.htm"],
+            _ => &["**/*.html"],
+        }
+    }
+
+    /// Globs of the generated pages that are not part of the graph.
+    pub fn exclude(self) -> &'static [&'static str] {
+        match self {
+            Preset::MdBook => &["404.html", "print.html", "toc.html"],
+            Preset::Next => &["404.html", "_next/**"],
+            _ => &["404.html"],
+        }
+    }
+
+    /// Selectors of the site-wide chrome, whose links would connect every page.
+    pub fn exclude_selectors(self) -> &'static [&'static str] {
+        match self {
+            Preset::MdBook => &["#sidebar", "#menu-bar", ".nav-chapters", ".nav-wrapper"],
+            _ => &["header", "footer", "nav"],
+        }
+    }
+
+    /// Extensions stripped from page URLs and internal links.
+    pub fn page_extensions(self) -> &'static [&'static str] {
+        match self {
+            Preset::Jekyll => &[".html", ".htm"],
+            _ => &[".html"],
+        }
+    }
+}
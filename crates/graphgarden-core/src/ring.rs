@@ -0,0 +1,462 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use html_escape::{encode_double_quoted_attribute, encode_text};
+use lol_html::html_content::ContentType;
+use lol_html::{RewriteStrSettings, element, rewrite_str};
+
+use crate::build::site_pages;
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::model::{Friend, Ring, RingMember, RingMembership, RingNavigation};
+
+/// Directory of the ring navigation files, relative to the output directory.
+pub const RING_DIR: &str = ".well-known/graphgarden-rings";
+
+/// Attribute of the elements whose content is replaced by a ring navigation
+/// bar, e.g. `<div data-graphgarden-ring="Garden Ring"></div>`. An empty
+/// value gets the bars of every ring.
+pub const RING_PLACEHOLDER_ATTRIBUTE: &str = "data-graphgarden-ring";
+
+/// Loads a `ring.toml` webring manifest.
+pub fn load_ring(path: impl AsRef<Path>) -> Result<Ring> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path).map_err(|e| Error::FileRead(e, path.to_path_buf()))?;
+    toml::from_str(&content).map_err(|e| Error::RingParse(path.to_path_buf(), e))
+}
+
+/// The membership of the site at `base_url` in `ring`, failing when it is not a member.
+pub fn membership(ring: &Ring, base_url: &str) -> Result<RingMembership> {
+    ring.membership(base_url)
+        .ok_or_else(|| Error::NotARingMember(ring.name.clone(), base_url.to_owned()))
+}
+
+/// Renders `friends` as a `friends = [...]` snippet for `graphgarden.toml`, one friend per line.
+pub fn friends_toml(friends: &[Friend]) -> Result<String> {
+    let mut out = String::from("friends = [\n");
+    for friend in friends {
+        let value = toml::Value::try_from(friend)?;
+        out.push_str(&format!("  {value},\n"));
+    }
+    out.push_str("]\n");
+    Ok(out)
+}
+
+/// Writes the navigation of every ring with `nav` enabled, returning the files written.
+///
+/// Each ring gets a directory under [`RING_DIR`] holding `nav.html`, a
+/// "← previous | ring | next →" bar that needs no JavaScript, `nav.json`, its
+/// [`RingNavigation`], and `random.html`, a page redirecting to a random
+/// member. Placeholders marked with [`RING_PLACEHOLDER_ATTRIBUTE`] in the
+/// pages of the site are filled with the bar. Directories of rings no longer
+/// configured are removed, and unchanged files are left untouched.
+pub fn write_ring_navigation(config: &Config) -> Result<Vec<PathBuf>> {
+    let base_url = &config.site.base_url;
+    let ring_dir = Path::new(&config.output.dir).join(RING_DIR);
+
+    let mut written = Vec::new();
+    let mut bars = Vec::new();
+    let mut slugs = HashSet::new();
+    for ring_config in config.rings.iter().filter(|ring| ring.nav) {
+        let ring = ring_config.load()?;
+        let navigation = ring
+            .navigation(base_url)
+            .ok_or_else(|| Error::NotARingMember(ring.name.clone(), base_url.clone()))?;
+
+        let slug = slug(&ring.name);
+        let dir = ring_dir.join(&slug);
+        let random_url = format!("{base_url}{RING_DIR}/{slug}/random.html");
+        let bar = nav_html(&navigation, &random_url);
+
+        for (file, content) in [
+            ("nav.html", bar.clone()),
+            ("nav.json", navigation.to_json()?),
+            ("random.html", random_html(&ring, base_url)?),
+        ] {
+            let path = dir.join(file);
+            if write_if_changed(&path, &content)? {
+                written.push(path);
+            }
+        }
+
+        bars.push((ring.name, bar));
+        slugs.insert(slug);
+    }
+
+    remove_stale_rings(&ring_dir, &slugs)?;
+
+    if !bars.is_empty() {
+        for (path, _) in site_pages(config)? {
+            let html = fs::read_to_string(&path).map_err(|e| Error::FileRead(e, path.clone()))?;
+            if !html.contains(RING_PLACEHOLDER_ATTRIBUTE) {
+                continue;
+            }
+            if write_if_changed(&path, &fill_placeholders(&html, &bars)?)? {
+                written.push(path);
+            }
+        }
+    }
+
+    Ok(written)
+}
+
+/// Directory name of a ring, e.g. `garden-ring` for "Garden Ring".
+fn slug(name: &str) -> String {
+    let slug = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        String::from("ring")
+    } else {
+        slug
+    }
+}
+
+fn member_link(member: &RingMember, rel: &str, label: impl Fn(&str) -> String) -> String {
+    let name = member.name.as_deref().unwrap_or(&member.url);
+    format!(
+        "<a rel=\"{rel}\" href=\"{}\">{}</a>",
+        encode_double_quoted_attribute(&member.url),
+        label(&encode_text(name)),
+    )
+}
+
+/// The "← previous | ring | next →" bar of a member.
+fn nav_html(navigation: &RingNavigation, random_url: &str) -> String {
+    let name = encode_text(&navigation.name);
+    let ring = match &navigation.url {
+        Some(url) => format!(
+            "<a href=\"{}\">{name}</a>",
+            encode_double_quoted_attribute(url)
+        ),
+        None => name.to_string(),
+    };
+    format!(
+        "<nav class=\"graphgarden-ring\" aria-label=\"{} webring\">\n  {} |\n  {ring} |\n  {}\n  <a href=\"{}\">random</a>\n</nav>\n",
+        encode_double_quoted_attribute(&navigation.name),
+        member_link(&navigation.prev, "prev", |name| format!("← {name}")),
+        member_link(&navigation.next, "next", |name| format!("{name} →")),
+        encode_double_quoted_attribute(random_url),
+    )
+}
+
+/// A page redirecting to a random other member, listing them without JavaScript.
+fn random_html(ring: &Ring, base_url: &str) -> Result<String> {
+    let others: Vec<&RingMember> = ring
+        .members
+        .iter()
+        .filter(|member| member.url != base_url)
+        .collect();
+    let urls: Vec<&str> = others.iter().map(|member| member.url.as_str()).collect();
+    let urls = serde_json::to_string(&urls)
+        .map_err(|e| Error::Protocol(graphgarden_protocol::Error::JsonSerialize(e)))?
+        .replace("</", "<\\/");
+    let name = encode_text(&ring.name);
+    let items: String = others
+        .iter()
+        .map(|member| {
+            format!(
+                "    <li><a href=\"{}\">{}</a></li>\n",
+                encode_double_quoted_attribute(&member.url),
+                encode_text(member.name.as_deref().unwrap_or(&member.url)),
+            )
+        })
+        .collect();
+
+    Ok(format!(
+        r#"<!doctype html>
+<html>
+<head>
+  <meta charset="utf-8">
+  <meta name="robots" content="noindex">
+  <title>Random member of {name}</title>
+  <script>
+    const members = {urls};
+    if (members.length > 0) location.replace(members[Math.floor(Math.random() * members.length)]);
+  </script>
+</head>
+<body>
+  <p>Members of {name}:</p>
+  <ul>
+{items}  </ul>
+</body>
+</html>
+"#
+    ))
+}
+
+/// Replaces the content of the ring placeholders of a page with the bars of their rings.
+fn fill_placeholders(html: &str, bars: &[(String, String)]) -> Result<String> {
+    rewrite_str(
+        html,
+        RewriteStrSettings {
+            element_content_handlers: vec![element!("[data-graphgarden-ring]", |el| {
+                let name = el
+                    .get_attribute(RING_PLACEHOLDER_ATTRIBUTE)
+                    .unwrap_or_default();
+                let content: String = bars
+                    .iter()
+                    .filter(|(ring, _)| name.is_empty() || *ring == name)
+                    .map(|(_, bar)| bar.as_str())
+                    .collect();
+                if !content.is_empty() {
+                    el.set_inner_content(&content, ContentType::Html);
+                }
+                Ok(())
+            })],
+            ..RewriteStrSettings::new()
+        },
+    )
+    .map_err(|err| Error::HtmlParse(err.to_string()))
+}
+
+/// Writes `content` to `path` unless it already holds it, returning whether it was written.
+fn write_if_changed(path: &Path, content: &str) -> Result<bool> {
+    if fs::read_to_string(path).is_ok_and(|existing| existing == content) {
+        return Ok(false);
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| Error::FileWrite(e, parent.to_path_buf()))?;
+    }
+    fs::write(path, content).map_err(|e| Error::FileWrite(e, path.to_path_buf()))?;
+    Ok(true)
+}
+
+/// Removes the navigation directories of rings other than `slugs`.
+fn remove_stale_rings(ring_dir: &Path, slugs: &HashSet<String>) -> Result<()> {
+    let entries = match fs::read_dir(ring_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(Error::FileRead(e, ring_dir.to_path_buf())),
+    };
+
+    for entry in entries {
+        let entry = entry.map_err(|e| Error::FileRead(e, ring_dir.to_path_buf()))?;
+        let stale = !slugs.contains(entry.file_name().to_string_lossy().as_ref());
+        if stale && entry.path().is_dir() {
+            fs::remove_dir_all(entry.path()).map_err(|e| Error::FileWrite(e, entry.path()))?;
+        }
+    }
+    let empty = fs::read_dir(ring_dir)
+        .map_err(|e| Error::FileRead(e, ring_dir.to_path_buf()))?
+        .next()
+        .is_none();
+    if empty {
+        fs::remove_dir(ring_dir).map_err(|e| Error::FileWrite(e, ring_dir.to_path_buf()))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{CacheConfig, OutputConfig, ParseConfig, RingConfig, SiteConfig};
+    use tempfile::TempDir;
+
+    const RING: &str = r#"
+        name = "Garden Ring"
+        url = "https://ring.garden/"
+        moderators = ["https://alice.dev/"]
+
+        [[members]]
+        url = "https://alice.dev/"
+        name = "Alice"
+
+        [[members]]
+        url = "https://bob.dev/"
+    "#;
+
+    #[test]
+    fn loads_a_toml_manifest() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("ring.toml");
+        fs::write(&path, RING).unwrap();
+
+        let ring = load_ring(&path).unwrap();
+        assert_eq!(ring.name, "Garden Ring");
+        assert_eq!(ring.members.len(), 2);
+        assert_eq!(ring.members[0].name.as_deref(), Some("Alice"));
+        assert!(ring.validate().is_empty());
+    }
+
+    #[test]
+    fn rejects_malformed_manifests() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("ring.toml");
+        fs::write(&path, "name = \"Garden Ring\"").unwrap();
+
+        let result = load_ring(&path);
+        assert!(matches!(result, Err(Error::RingParse(_, _))));
+    }
+
+    #[test]
+    fn membership_requires_a_member() {
+        let ring: Ring = toml::from_str(RING).unwrap();
+
+        assert_eq!(
+            membership(&ring, "https://bob.dev/").unwrap().next,
+            "https://alice.dev/"
+        );
+        let result = membership(&ring, "https://mallory.dev/");
+        assert!(matches!(result, Err(Error::NotARingMember(_, _))));
+    }
+
+    fn nav_config(output_dir: &Path) -> Config {
+        let ring: Ring = toml::from_str(RING).unwrap();
+        Config {
+            site: SiteConfig {
+                base_url: String::from("https://bob.dev/"),
+                title: String::from("Bob's Garden"),
+                description: None,
+                language: None,
+            },
+            friends: vec![],
+            output: OutputConfig {
+                dir: output_dir.to_string_lossy().into_owned(),
+                ..OutputConfig::default()
+            },
+            cache: CacheConfig::default(),
+            parse: ParseConfig::default(),
+            groups: vec![],
+            edge_types: vec![],
+            contexts: vec![],
+            rings: vec![RingConfig {
+                name: Some(ring.name),
+                url: ring.url,
+                members: ring.members,
+                nav: true,
+                ..RingConfig::default()
+            }],
+            link_sources: None,
+        }
+    }
+
+    #[test]
+    fn slug_is_lowercase_and_dashed() {
+        assert_eq!(slug("Garden Ring"), "garden-ring");
+        assert_eq!(slug("  Indie/Web  Ring! "), "indie-web-ring");
+        assert_eq!(slug("庭"), "ring");
+    }
+
+    #[test]
+    fn nav_links_neighbors_and_escapes_names() {
+        let mut ring: Ring = toml::from_str(RING).unwrap();
+        ring.members[0].name = Some(String::from("Alice & co"));
+        let navigation = ring.navigation("https://bob.dev/").unwrap();
+
+        let html = nav_html(&navigation, "https://bob.dev/random.html");
+        assert!(html.contains(r#"<a rel="prev" href="https://alice.dev/">← Alice &amp; co</a>"#));
+        assert!(html.contains(r#"<a href="https://ring.garden/">Garden Ring</a>"#));
+        assert!(html.contains(r#"<a rel="next" href="https://alice.dev/">Alice &amp; co →</a>"#));
+        assert!(html.contains(r#"<a href="https://bob.dev/random.html">random</a>"#));
+        assert!(!html.contains("<script"));
+    }
+
+    #[test]
+    fn random_page_lists_the_other_members() {
+        let ring: Ring = toml::from_str(RING).unwrap();
+        let html = random_html(&ring, "https://bob.dev/").unwrap();
+
+        assert!(html.contains(r#"const members = ["https://alice.dev/"];"#));
+        assert!(html.contains(r#"<li><a href="https://alice.dev/">Alice</a></li>"#));
+        assert!(!html.contains(r#"href="https://bob.dev/""#));
+    }
+
+    #[test]
+    fn writes_navigation_and_fills_placeholders() {
+        let tmp = TempDir::new().unwrap();
+        let config = nav_config(tmp.path());
+        fs::write(
+            tmp.path().join("index.html"),
+            r#"<html><body><footer><div data-graphgarden-ring="Garden Ring">ring</div></footer></body></html>"#,
+        )
+        .unwrap();
+        fs::write(tmp.path().join("about.html"), "<html><body></body></html>").unwrap();
+
+        let written = write_ring_navigation(&config).unwrap();
+        let dir = tmp.path().join(RING_DIR).join("garden-ring");
+        assert_eq!(
+            written,
+            vec![
+                dir.join("nav.html"),
+                dir.join("nav.json"),
+                dir.join("random.html"),
+                tmp.path().join("index.html"),
+            ]
+        );
+
+        let navigation =
+            RingNavigation::from_json(&fs::read_to_string(dir.join("nav.json")).unwrap()).unwrap();
+        assert_eq!(navigation.next.url, "https://alice.dev/");
+        let page = fs::read_to_string(tmp.path().join("index.html")).unwrap();
+        assert!(
+            page.contains(
+                r#"<div data-graphgarden-ring="Garden Ring"><nav class="graphgarden-ring""#
+            )
+        );
+        assert!(page.contains(r#"rel="next" href="https://alice.dev/""#));
+
+        assert!(
+            write_ring_navigation(&config).unwrap().is_empty(),
+            "unchanged files are left untouched"
+        );
+    }
+
+    #[test]
+    fn removes_navigation_of_disabled_rings() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = nav_config(tmp.path());
+        write_ring_navigation(&config).unwrap();
+        assert!(tmp.path().join(RING_DIR).join("garden-ring").is_dir());
+
+        config.rings[0].nav = false;
+        assert!(write_ring_navigation(&config).unwrap().is_empty());
+        assert!(!tmp.path().join(RING_DIR).exists());
+    }
+
+    #[test]
+    fn navigation_requires_membership() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = nav_config(tmp.path());
+        config.site.base_url = String::from("https://mallory.dev/");
+
+        let result = write_ring_navigation(&config);
+        assert!(matches!(result, Err(Error::NotARingMember(_, _))));
+    }
+
+    #[test]
+    fn navigation_rejects_invalid_members() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = nav_config(tmp.path());
+        config.rings[0].members[0].url = String::from("javascript:alert(1)");
+
+        let result = write_ring_navigation(&config);
+        assert!(
+            matches!(result, Err(Error::InvalidRing(_, ref reason)) if reason.contains("javascript:alert(1)"))
+        );
+        assert!(!tmp.path().join(RING_DIR).exists());
+    }
+
+    #[test]
+    fn friends_toml_is_a_config_snippet() {
+        let ring: Ring = toml::from_str(RING).unwrap();
+        let snippet = friends_toml(&ring.friends_of("https://bob.dev/").unwrap()).unwrap();
+
+        assert_eq!(
+            snippet,
+            "friends = [\n  { name = \"Alice\", url = \"https://alice.dev/\" },\n]\n"
+        );
+
+        #[derive(serde::Deserialize)]
+        struct Snippet {
+            friends: Vec<Friend>,
+        }
+        let parsed: Snippet = toml::from_str(&snippet).unwrap();
+        assert_eq!(parsed.friends, ring.friends_of("https://bob.dev/").unwrap());
+    }
+}
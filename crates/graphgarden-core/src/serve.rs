@@ -0,0 +1,611 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+use percent_encoding::{AsciiSet, CONTROLS, percent_decode_str, utf8_percent_encode};
+use serde_json::Value;
+use tiny_http::{Header, Method, Request, Response};
+use url::Url;
+
+use crate::error::{Error, Result};
+use crate::output::WELL_KNOWN_PATH;
+
+/// Path prefix of the friend sites mounted by the preview server, followed by their host.
+pub const FRIENDS_PREFIX: &str = "/_friends/";
+
+/// `Cache-Control` max age of the protocol files, as recommended by the specification.
+pub const DEFAULT_MAX_AGE: u32 = 3600;
+
+/// Characters percent-encoded in a URL path, as the URL standard's path percent-encode set.
+const PATH: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+/// A local directory standing in for the site of a friend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FriendMount {
+    /// Base URL of the friend, as declared in `friends`.
+    pub url: Url,
+    /// Output directory of the friend's site.
+    pub dir: PathBuf,
+}
+
+impl FriendMount {
+    /// Parses a `URL=DIR` mount, e.g. `https://bob.dev/=../bob/dist`.
+    pub fn parse(input: &str) -> Result<Self> {
+        let invalid = |reason: &str| Error::InvalidFriendMount(input.to_owned(), reason.to_owned());
+
+        let (url, dir) = input
+            .split_once('=')
+            .ok_or_else(|| invalid("expected URL=DIR"))?;
+        let url = Url::parse(url).map_err(|error| invalid(&error.to_string()))?;
+        if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+            return Err(invalid("the URL must use http or https"));
+        }
+        if dir.is_empty() {
+            return Err(invalid("the directory is empty"));
+        }
+
+        Ok(FriendMount {
+            url,
+            dir: PathBuf::from(dir),
+        })
+    }
+
+    /// Path of the mount on the preview server, e.g. `/_friends/bob.dev/`.
+    pub fn path(&self) -> String {
+        format!("{FRIENDS_PREFIX}{}/", self.url.authority())
+    }
+}
+
+/// What the preview server serves, and how.
+#[derive(Debug, Clone)]
+pub struct ServeOptions {
+    /// Output directory of the site.
+    pub root: PathBuf,
+    /// Friend sites served from local directories instead of their origin.
+    pub friends: Vec<FriendMount>,
+    /// `Cache-Control` max age of the protocol files, in seconds.
+    pub max_age: u32,
+}
+
+impl ServeOptions {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        ServeOptions {
+            root: root.into(),
+            friends: Vec::new(),
+            max_age: DEFAULT_MAX_AGE,
+        }
+    }
+}
+
+/// A request answered by the preview server, for logging.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Served {
+    pub method: String,
+    pub url: String,
+    pub status: u16,
+}
+
+/// A static file server to preview a site and its garden locally.
+///
+/// Files under `.well-known/graphgarden` are served with the headers the
+/// specification asks of hosts: an `ETag`, `Cache-Control: public, max-age`
+/// and `Access-Control-Allow-Origin: *`. Other files get an `ETag` and
+/// `Cache-Control: no-cache`, so rebuilds show up on reload.
+///
+/// Each [`FriendMount`] is served under [`FriendMount::path`], and the
+/// `friends` of the public files served point their `public_file` there, so
+/// the web component fetches the local copies. The files on disk are left
+/// untouched, which also means a rewritten file no longer matches its signature.
+pub struct Server {
+    http: tiny_http::Server,
+    options: ServeOptions,
+}
+
+/// A response before it is sent, kept apart from the connection for testing.
+#[derive(Debug)]
+struct Reply {
+    status: u16,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+}
+
+impl Reply {
+    fn text(status: u16, body: &str) -> Self {
+        Reply {
+            status,
+            headers: vec![("Content-Type", String::from("text/plain; charset=utf-8"))],
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[cfg(test)]
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl Server {
+    /// Binds the server to `address`, e.g. `127.0.0.1:8080`, or port 0 for any free port.
+    pub fn bind(address: &str, options: ServeOptions) -> Result<Self> {
+        if !options.root.is_dir() {
+            return Err(Error::OutputDirNotFound(options.root));
+        }
+        for friend in &options.friends {
+            if !friend.dir.is_dir() {
+                return Err(Error::OutputDirNotFound(friend.dir.clone()));
+            }
+        }
+
+        let http = tiny_http::Server::http(address)
+            .map_err(|error| Error::Serve(address.to_owned(), error.to_string()))?;
+        Ok(Server { http, options })
+    }
+
+    /// The address the server listens on.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.http.server_addr().to_ip()
+    }
+
+    /// Answers requests until the process ends, calling `log` after each of them.
+    pub fn run(&self, mut log: impl FnMut(&Served)) {
+        for request in self.http.incoming_requests() {
+            let served = self.answer(request);
+            log(&served);
+        }
+    }
+
+    fn answer(&self, request: Request) -> Served {
+        let header = |name: &'static str| {
+            request
+                .headers()
+                .iter()
+                .find(|header| header.field.equiv(name))
+                .map(|header| header.value.as_str().to_owned())
+        };
+        let host = header("Host")
+            .or_else(|| self.local_addr().map(|address| address.to_string()))
+            .unwrap_or_default();
+        let if_none_match = header("If-None-Match");
+
+        let method = request.method().clone();
+        let url = request.url().to_owned();
+        let reply = self.respond(&method, &url, &host, if_none_match.as_deref());
+
+        let status = reply.status;
+        // Bodies are read whole, so sending them with a `Content-Length` is
+        // always possible, and keeps it on the replies to HEAD requests
+        let mut response = Response::from_data(reply.body)
+            .with_status_code(status)
+            .with_chunked_threshold(usize::MAX);
+        for (name, value) in reply.headers {
+            if let Ok(header) = Header::from_bytes(name, value) {
+                response.add_header(header);
+            }
+        }
+        // The client may have gone away, which only matters to the client
+        let _ = request.respond(response);
+
+        Served {
+            method: method.to_string(),
+            url,
+            status,
+        }
+    }
+
+    fn respond(
+        &self,
+        method: &Method,
+        url: &str,
+        host: &str,
+        if_none_match: Option<&str>,
+    ) -> Reply {
+        let mut reply = self.lookup(method, url, host, if_none_match);
+        // HEAD gets the headers of GET, including the length of the body it leaves out
+        if *method == Method::Head && reply.status != 304 {
+            reply
+                .headers
+                .push(("Content-Length", reply.body.len().to_string()));
+            reply.body.clear();
+        }
+        reply
+    }
+
+    fn lookup(&self, method: &Method, url: &str, host: &str, if_none_match: Option<&str>) -> Reply {
+        if !matches!(method, Method::Get | Method::Head) {
+            let mut reply = Reply::text(405, "method not allowed");
+            reply.headers.push(("Allow", String::from("GET, HEAD")));
+            return reply;
+        }
+
+        let path = url.split(['?', '#']).next().unwrap_or_default();
+        let Ok(path) = percent_decode_str(path).decode_utf8() else {
+            return Reply::text(400, "invalid path");
+        };
+        let Some((root, relative)) = self.mount(&path) else {
+            return Reply::text(404, "not found");
+        };
+        let Some(segments) = safe_segments(relative) else {
+            return Reply::text(400, "invalid path");
+        };
+
+        let mut file = root.to_path_buf();
+        file.extend(&segments);
+        if relative.is_empty() || relative.ends_with('/') {
+            file.push("index.html");
+        } else if file.is_dir() {
+            let mut reply = Reply::text(301, "moved permanently");
+            // Headers must be ASCII, so the decoded path is encoded again
+            let location = utf8_percent_encode(&path, PATH);
+            reply.headers.push(("Location", format!("{location}/")));
+            return reply;
+        } else if !file.is_file() {
+            // Appended rather than set, so `/v1.2` looks up `v1.2.html`
+            let mut name = file.into_os_string();
+            name.push(".html");
+            file = PathBuf::from(name);
+        }
+
+        let (status, file) = if file.is_file() {
+            (200, file)
+        } else {
+            (404, root.join("404.html"))
+        };
+        let Ok(mut body) = fs::read(&file) else {
+            return Reply::text(404, "not found");
+        };
+
+        let relative = relative.trim_start_matches('/');
+        let protocol_file = relative.starts_with(".well-known/graphgarden");
+        if status == 200 && relative == WELL_KNOWN_PATH && !self.options.friends.is_empty() {
+            body = self.mount_friends(body, host);
+        }
+
+        let mut headers = vec![
+            ("Content-Type", String::from(content_type(&file))),
+            ("ETag", etag(&body)),
+        ];
+        if protocol_file {
+            headers.push((
+                "Cache-Control",
+                format!("public, max-age={}", self.options.max_age),
+            ));
+            headers.push(("Access-Control-Allow-Origin", String::from("*")));
+        } else {
+            headers.push(("Cache-Control", String::from("no-cache")));
+        }
+
+        let etag_matches = if_none_match.is_some_and(|tags| {
+            let current = etag(&body);
+            tags.split(',')
+                .any(|tag| matches!(tag.trim(), "*") || tag.trim() == current)
+        });
+        if status == 200 && etag_matches {
+            return Reply {
+                status: 304,
+                headers,
+                body: Vec::new(),
+            };
+        }
+
+        Reply {
+            status,
+            headers,
+            body,
+        }
+    }
+
+    /// The directory serving `path`, and the path within it.
+    fn mount<'p>(&self, path: &'p str) -> Option<(&Path, &'p str)> {
+        let Some(rest) = path.strip_prefix(FRIENDS_PREFIX) else {
+            return Some((&self.options.root, path));
+        };
+        let (authority, relative) = rest.split_once('/').unwrap_or((rest, ""));
+        self.options
+            .friends
+            .iter()
+            .find(|friend| friend.url.authority() == authority)
+            .map(|friend| (friend.dir.as_path(), relative))
+    }
+
+    /// Points the mounted `friends` of a public file to their local copy.
+    ///
+    /// Works on the raw JSON, so that any encoding is kept as is, and leaves
+    /// files that cannot be parsed untouched.
+    fn mount_friends(&self, body: Vec<u8>, host: &str) -> Vec<u8> {
+        let Ok(mut file) = serde_json::from_slice::<Value>(&body) else {
+            return body;
+        };
+        let Some(friends) = file.get_mut("friends").and_then(Value::as_array_mut) else {
+            return body;
+        };
+
+        for friend in friends.iter_mut() {
+            let url = match friend {
+                Value::String(url) => url.clone(),
+                Value::Object(object) => match object.get("url") {
+                    Some(Value::String(url)) => url.clone(),
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            let Ok(parsed) = Url::parse(&url) else {
+                continue;
+            };
+            let Some(mount) = self
+                .options
+                .friends
+                .iter()
+                .find(|mount| mount.url.origin() == parsed.origin())
+            else {
+                continue;
+            };
+
+            let public_file = format!("http://{host}{}{WELL_KNOWN_PATH}", mount.path());
+            if let Value::String(_) = friend {
+                *friend = serde_json::json!({ "url": url });
+            }
+            if let Value::Object(object) = friend {
+                object.insert(String::from("public_file"), Value::String(public_file));
+            }
+        }
+
+        serde_json::to_vec(&file).unwrap_or(body)
+    }
+}
+
+/// The segments of a relative path, or `None` when it would leave its directory.
+fn safe_segments(relative: &str) -> Option<Vec<&str>> {
+    relative
+        .split('/')
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .map(|segment| {
+            let unsafe_segment = segment == ".." || segment.contains(['\\', ':', '\0']);
+            (!unsafe_segment).then_some(segment)
+        })
+        .collect()
+}
+
+fn etag(body: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    format!("\"{:016x}\"", hasher.finish())
+}
+
+fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("html" | "htm") => "text/html; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("js" | "mjs") => "text/javascript; charset=utf-8",
+        Some("json") => "application/json",
+        Some("xml") => "application/xml",
+        Some("txt" | "sig") => "text/plain; charset=utf-8",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("avif") => "image/avif",
+        Some("ico") => "image/x-icon",
+        Some("woff2") => "font/woff2",
+        Some("woff") => "font/woff",
+        Some("wasm") => "application/wasm",
+        Some("gz") => "application/gzip",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, relative: &str, content: &str) {
+        let path = dir.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn server(root: &Path, friends: Vec<FriendMount>) -> Server {
+        let options = ServeOptions {
+            friends,
+            ..ServeOptions::new(root)
+        };
+        Server::bind("127.0.0.1:0", options).unwrap()
+    }
+
+    fn get(server: &Server, url: &str) -> Reply {
+        server.respond(&Method::Get, url, "localhost:8080", None)
+    }
+
+    #[test]
+    fn parses_friend_mounts() {
+        let mount = FriendMount::parse("https://bob.dev/=../bob/dist").unwrap();
+        assert_eq!(mount.url.as_str(), "https://bob.dev/");
+        assert_eq!(mount.dir, PathBuf::from("../bob/dist"));
+        assert_eq!(mount.path(), "/_friends/bob.dev/");
+
+        let mount = FriendMount::parse("http://localhost:4000/=bob").unwrap();
+        assert_eq!(mount.path(), "/_friends/localhost:4000/");
+
+        for input in [
+            "https://bob.dev/",
+            "bob.dev=bob",
+            "ftp://bob.dev/=bob",
+            "https://bob.dev/=",
+        ] {
+            let result = FriendMount::parse(input);
+            assert!(
+                matches!(result, Err(Error::InvalidFriendMount(_, _))),
+                "{input} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn serves_protocol_headers_on_the_well_known_file() {
+        let tmp = TempDir::new().unwrap();
+        write(tmp.path(), WELL_KNOWN_PATH, r#"{"version":"0.1.0"}"#);
+        let server = server(tmp.path(), Vec::new());
+
+        let reply = get(&server, "/.well-known/graphgarden.json");
+        assert_eq!(reply.status, 200);
+        assert_eq!(reply.header("Content-Type"), Some("application/json"));
+        assert_eq!(reply.header("Cache-Control"), Some("public, max-age=3600"));
+        assert_eq!(reply.header("Access-Control-Allow-Origin"), Some("*"));
+        assert_eq!(reply.body, br#"{"version":"0.1.0"}"#);
+
+        let etag = reply.header("ETag").unwrap().to_owned();
+        let reply = server.respond(
+            &Method::Get,
+            "/.well-known/graphgarden.json",
+            "localhost:8080",
+            Some(&etag),
+        );
+        assert_eq!(reply.status, 304);
+        assert!(reply.body.is_empty());
+        assert_eq!(reply.header("ETag"), Some(etag.as_str()));
+    }
+
+    #[test]
+    fn serves_pages_with_pretty_urls() {
+        let tmp = TempDir::new().unwrap();
+        write(tmp.path(), "index.html", "home");
+        write(tmp.path(), "blog/index.html", "blog");
+        write(tmp.path(), "about.html", "about");
+        write(tmp.path(), "404.html", "lost");
+        let server = server(tmp.path(), Vec::new());
+
+        let reply = get(&server, "/");
+        assert_eq!(
+            (reply.status, reply.body.as_slice()),
+            (200, b"home".as_slice())
+        );
+        assert_eq!(
+            reply.header("Content-Type"),
+            Some("text/html; charset=utf-8")
+        );
+        assert_eq!(reply.header("Cache-Control"), Some("no-cache"));
+        assert_eq!(reply.header("Access-Control-Allow-Origin"), None);
+
+        assert_eq!(get(&server, "/blog/").body, b"blog");
+        assert_eq!(get(&server, "/about?ref=home").body, b"about");
+
+        let reply = get(&server, "/blog");
+        assert_eq!(reply.status, 301);
+        assert_eq!(reply.header("Location"), Some("/blog/"));
+
+        write(tmp.path(), "v1.2.html", "release");
+        write(tmp.path(), "notes.md", "markdown");
+        write(tmp.path(), "notes.html", "notes");
+        assert_eq!(get(&server, "/v1.2").body, b"release");
+        assert_eq!(get(&server, "/notes.md").body, b"markdown");
+        assert_eq!(get(&server, "/notes").body, b"notes");
+
+        write(tmp.path(), "café à/index.html", "café");
+        let reply = get(&server, "/caf%C3%A9%20%C3%A0");
+        assert_eq!(reply.status, 301);
+        assert_eq!(reply.header("Location"), Some("/caf%C3%A9%20%C3%A0/"));
+        assert_eq!(get(&server, "/caf%C3%A9%20%C3%A0/").body, "café".as_bytes());
+
+        let reply = get(&server, "/missing/");
+        assert_eq!(
+            (reply.status, reply.body.as_slice()),
+            (404, b"lost".as_slice())
+        );
+    }
+
+    #[test]
+    fn head_keeps_the_headers_without_the_body() {
+        let tmp = TempDir::new().unwrap();
+        write(tmp.path(), "index.html", "home");
+        let server = server(tmp.path(), Vec::new());
+
+        let reply = server.respond(&Method::Head, "/", "localhost:8080", None);
+        assert_eq!(reply.status, 200);
+        assert!(reply.body.is_empty());
+        assert_eq!(reply.header("Content-Length"), Some("4"));
+        assert_eq!(reply.header("ETag"), get(&server, "/").header("ETag"));
+        assert_eq!(
+            reply.header("Content-Type"),
+            Some("text/html; charset=utf-8")
+        );
+    }
+
+    #[test]
+    fn rejects_paths_leaving_the_root() {
+        let tmp = TempDir::new().unwrap();
+        write(tmp.path(), "secret.txt", "secret");
+        let root = tmp.path().join("dist");
+        write(&root, "index.html", "home");
+        let server = server(&root, Vec::new());
+
+        assert_eq!(get(&server, "/../secret.txt").status, 400);
+        assert_eq!(get(&server, "/%2e%2e/secret.txt").status, 400);
+        assert_eq!(
+            server
+                .respond(&Method::Post, "/", "localhost:8080", None)
+                .status,
+            405
+        );
+    }
+
+    #[test]
+    fn mounts_friends_and_points_to_their_local_copy() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().join("alice");
+        let bob = tmp.path().join("bob");
+        write(
+            &root,
+            WELL_KNOWN_PATH,
+            r#"{"friends":["https://bob.dev/",{"url":"https://carol.dev/","name":"Carol"}]}"#,
+        );
+        write(&bob, WELL_KNOWN_PATH, r#"{"friends":[]}"#);
+        let mount = FriendMount::parse(&format!("https://bob.dev/={}", bob.display())).unwrap();
+        let server = server(&root, vec![mount]);
+
+        let reply = get(&server, "/.well-known/graphgarden.json");
+        let file: Value = serde_json::from_slice(&reply.body).unwrap();
+        assert_eq!(
+            file["friends"],
+            serde_json::json!([
+                {
+                    "url": "https://bob.dev/",
+                    "public_file": "http://localhost:8080/_friends/bob.dev/.well-known/graphgarden.json"
+                },
+                { "url": "https://carol.dev/", "name": "Carol" }
+            ])
+        );
+
+        let reply = get(&server, "/_friends/bob.dev/.well-known/graphgarden.json");
+        assert_eq!(reply.status, 200);
+        assert_eq!(reply.header("Access-Control-Allow-Origin"), Some("*"));
+        assert_eq!(reply.body, br#"{"friends":[]}"#);
+
+        assert_eq!(get(&server, "/_friends/carol.dev/").status, 404);
+    }
+
+    #[test]
+    fn bind_requires_the_directories() {
+        let tmp = TempDir::new().unwrap();
+        let result = Server::bind("127.0.0.1:0", ServeOptions::new(tmp.path().join("dist")));
+        assert!(matches!(result, Err(Error::OutputDirNotFound(_))));
+    }
+}
@@ -0,0 +1,170 @@
+use std::fs;
+use std::path::Path;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+pub use ed25519_dalek::SigningKey;
+use ed25519_dalek::{Signature, Signer, Verifier, VerifyingKey};
+
+use crate::error::{Error, Result};
+use crate::model::PublicFile;
+
+pub use crate::output::SIGNATURE_EXTENSION;
+
+/// Generates a new ed25519 signing key from the system's random source.
+pub fn generate_signing_key() -> Result<SigningKey> {
+    let mut seed = [0u8; 32];
+    getrandom::fill(&mut seed).map_err(|error| Error::KeyGeneration(error.to_string()))?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+/// Encodes a signing key as the base64 of its 32-byte seed, the key file format.
+pub fn encode_signing_key(key: &SigningKey) -> String {
+    STANDARD.encode(key.to_bytes())
+}
+
+/// Reads a key file written by [`encode_signing_key`].
+pub fn read_signing_key(path: &Path) -> Result<SigningKey> {
+    let content = fs::read_to_string(path).map_err(|e| Error::FileRead(e, path.to_path_buf()))?;
+    let seed = decode_array::<32>(content.trim())
+        .ok_or_else(|| Error::InvalidSigningKey(path.to_path_buf()))?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+/// The base64 public key of a signing key, as declared in `site.public_key`.
+pub fn public_key(key: &SigningKey) -> String {
+    STANDARD.encode(key.verifying_key().to_bytes())
+}
+
+/// Signs the canonical form of a public file, returning the base64 signature.
+///
+/// Set `site.public_key` to [`public_key`] beforehand, so the key is covered
+/// by the signature and consumers can find it.
+pub fn sign_public_file(file: &PublicFile, key: &SigningKey) -> Result<String> {
+    let signature = key.sign(file.canonical_json()?.as_bytes());
+    Ok(STANDARD.encode(signature.to_bytes()))
+}
+
+/// Verifies a base64 signature of a public file.
+///
+/// The signature is checked against `pinned` when set, e.g. a friend's
+/// [`Friend::public_key`](crate::model::Friend::public_key), which must then
+/// match the key declared in `site.public_key` if any. Otherwise, it is
+/// checked against the declared key.
+pub fn verify_public_file(file: &PublicFile, signature: &str, pinned: Option<&str>) -> Result<()> {
+    let declared = file.site.public_key.as_deref();
+    let key = match (pinned, declared) {
+        (Some(pinned), Some(declared)) if pinned != declared => {
+            return Err(Error::PublicKeyMismatch(
+                declared.to_owned(),
+                pinned.to_owned(),
+            ));
+        }
+        (Some(key), _) | (None, Some(key)) => key,
+        (None, None) => return Err(Error::MissingPublicKey),
+    };
+
+    let key = decode_array::<32>(key)
+        .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok())
+        .ok_or_else(|| Error::InvalidPublicKey(key.to_owned()))?;
+    let signature = decode_array::<64>(signature.trim())
+        .map(|bytes| Signature::from_bytes(&bytes))
+        .ok_or(Error::InvalidSignature)?;
+
+    key.verify(file.canonical_json()?.as_bytes(), &signature)
+        .map_err(|_| Error::InvalidSignature)
+}
+
+fn decode_array<const N: usize>(encoded: &str) -> Option<[u8; N]> {
+    STANDARD.decode(encoded).ok()?.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Extensions, Node, SiteMetadata};
+    use tempfile::TempDir;
+
+    fn sample_file(key: &SigningKey) -> PublicFile {
+        PublicFile {
+            version: String::from(crate::model::PROTOCOL_VERSION),
+            generated_at: String::from("2026-02-17T12:00:00Z"),
+            base_url: String::from("https://alice.dev/"),
+            site: SiteMetadata {
+                title: String::from("Alice's Garden"),
+                description: None,
+                language: None,
+                public_key: Some(public_key(key)),
+                extensions: Extensions::new(),
+            },
+            friends: Vec::new(),
+            nodes: vec![Node {
+                url: String::from("/"),
+                title: String::from("Home"),
+                group: None,
+                extensions: Extensions::new(),
+            }],
+            edges: Vec::new(),
+            rings: Vec::new(),
+            shards: Vec::new(),
+            extensions: Extensions::new(),
+        }
+    }
+
+    #[test]
+    fn signed_file_verifies_against_declared_and_pinned_key() {
+        let key = generate_signing_key().unwrap();
+        let file = sample_file(&key);
+        let signature = sign_public_file(&file, &key).unwrap();
+
+        verify_public_file(&file, &signature, None).unwrap();
+        verify_public_file(&file, &signature, Some(&public_key(&key))).unwrap();
+    }
+
+    #[test]
+    fn tampered_file_fails_verification() {
+        let key = generate_signing_key().unwrap();
+        let mut file = sample_file(&key);
+        let signature = sign_public_file(&file, &key).unwrap();
+
+        file.nodes[0].title = String::from("Mallory's Home");
+        let result = verify_public_file(&file, &signature, None);
+        assert!(matches!(result, Err(Error::InvalidSignature)));
+    }
+
+    #[test]
+    fn rejects_files_declaring_another_key_than_pinned() {
+        let key = generate_signing_key().unwrap();
+        let other = generate_signing_key().unwrap();
+        let file = sample_file(&other);
+        let signature = sign_public_file(&file, &other).unwrap();
+
+        let result = verify_public_file(&file, &signature, Some(&public_key(&key)));
+        assert!(matches!(result, Err(Error::PublicKeyMismatch(_, _))));
+    }
+
+    #[test]
+    fn requires_a_public_key() {
+        let key = generate_signing_key().unwrap();
+        let mut file = sample_file(&key);
+        file.site.public_key = None;
+        let signature = sign_public_file(&file, &key).unwrap();
+
+        let result = verify_public_file(&file, &signature, None);
+        assert!(matches!(result, Err(Error::MissingPublicKey)));
+    }
+
+    #[test]
+    fn key_file_round_trips() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("graphgarden.key");
+        let key = generate_signing_key().unwrap();
+        fs::write(&path, format!("{}\n", encode_signing_key(&key))).unwrap();
+
+        assert_eq!(read_signing_key(&path).unwrap(), key);
+
+        fs::write(&path, "not a key").unwrap();
+        let result = read_signing_key(&path);
+        assert!(matches!(result, Err(Error::InvalidSigningKey(_))));
+    }
+}
@@ -0,0 +1,142 @@
+//! Canonical serialization of the public file, the input of its signature.
+//!
+//! The canonical form is the expanded form without `shards`, where nodes and
+//! edges are sorted, object keys are sorted, and no whitespace is emitted. It
+//! doesn't depend on the encoding or sharding of the file, nor on the order of
+//! its nodes and edges.
+
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+use crate::model::PublicFile;
+
+impl PublicFile {
+    /// Serializes the file in its canonical form, the bytes covered by its signature.
+    pub fn canonical_json(&self) -> Result<String> {
+        let mut value = serde_json::to_value(self).map_err(Error::JsonSerialize)?;
+        if let Value::Object(object) = &mut value {
+            object.remove("shards");
+            for key in ["nodes", "edges"] {
+                if let Some(Value::Array(items)) = object.get_mut(key) {
+                    let mut canonical: Vec<String> = items.iter().map(canonical_string).collect();
+                    canonical.sort();
+                    *items = canonical
+                        .iter()
+                        .map(|item| serde_json::from_str(item).map_err(Error::JsonDeserialize))
+                        .collect::<Result<_>>()?;
+                }
+            }
+        }
+
+        Ok(canonical_string(&value))
+    }
+}
+
+fn canonical_string(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(object) => {
+            let mut entries: Vec<_> = object.iter().collect();
+            entries.sort_by_key(|(key, _)| key.as_str());
+            out.push('{');
+            for (index, (key, value)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(value, out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{Edge, EdgeType, Extensions, Node, PublicFile, SiteMetadata};
+    use crate::shard::ShardBy;
+
+    fn sample_file() -> PublicFile {
+        let node = |url: &str| Node {
+            url: String::from(url),
+            title: String::from("Page"),
+            group: None,
+            extensions: Extensions::new(),
+        };
+        let edge = |source: &str, target: &str| Edge {
+            source: String::from(source),
+            target: String::from(target),
+            edge_type: EdgeType::Internal,
+            context: None,
+            extensions: Extensions::new(),
+        };
+        PublicFile {
+            version: String::from(crate::PROTOCOL_VERSION),
+            generated_at: String::from("2026-02-17T12:00:00Z"),
+            base_url: String::from("https://alice.dev/"),
+            site: SiteMetadata {
+                title: String::from("Alice's Garden"),
+                description: None,
+                language: None,
+                public_key: None,
+                extensions: Extensions::new(),
+            },
+            friends: Vec::new(),
+            nodes: vec![node("/"), node("/zeta"), node("/blog/hello")],
+            edges: vec![edge("/zeta", "/"), edge("/", "/blog/hello")],
+            rings: Vec::new(),
+            shards: Vec::new(),
+            extensions: Extensions::new(),
+        }
+    }
+
+    #[test]
+    fn canonical_form_is_compact_with_sorted_keys() {
+        let canonical = sample_file().canonical_json().unwrap();
+
+        assert!(canonical.starts_with(r#"{"base_url":"https://alice.dev/","edges":[{"#));
+        assert!(!canonical.contains("\": ") && !canonical.contains('\n'));
+    }
+
+    #[test]
+    fn canonical_form_ignores_node_and_edge_order() {
+        let original = sample_file();
+        let mut reordered = original.clone();
+        reordered.nodes.reverse();
+        reordered.edges.reverse();
+
+        assert_eq!(
+            original.canonical_json().unwrap(),
+            reordered.canonical_json().unwrap()
+        );
+    }
+
+    #[test]
+    fn canonical_form_survives_sharding() {
+        let original = sample_file();
+        let (index, shards) = original.clone().into_shards(ShardBy::Prefix);
+        let reassembled = index.reassemble(shards).unwrap();
+
+        assert_eq!(
+            original.canonical_json().unwrap(),
+            reassembled.canonical_json().unwrap()
+        );
+    }
+}
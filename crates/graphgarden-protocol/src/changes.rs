@@ -0,0 +1,275 @@
+//! Change feed: what changed in a site's graph between successive builds.
+//!
+//! Published next to the public file as `graphgarden-changes.json`, newest
+//! generation first, so consumers can poll it instead of the whole graph.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::model::{Edge, EdgeType, Node, PublicFile};
+
+/// The changes between two generations of a public file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Changes {
+    /// `generated_at` of the previous file.
+    pub from: String,
+    /// `generated_at` of the new file.
+    pub to: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub added_nodes: Vec<Node>,
+    /// URLs of the removed nodes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed_nodes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub renamed_nodes: Vec<Rename>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub added_edges: Vec<Edge>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed_edges: Vec<Edge>,
+}
+
+/// A node whose title changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rename {
+    pub url: String,
+    pub from: String,
+    pub to: String,
+}
+
+impl Changes {
+    /// Whether nothing changed, besides `generated_at`.
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.renamed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+}
+
+/// The last generations of changes of a site, newest first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeFeed {
+    /// Protocol version the feed was generated for.
+    pub version: String,
+    pub generations: Vec<Changes>,
+}
+
+impl Default for ChangeFeed {
+    fn default() -> Self {
+        ChangeFeed {
+            version: String::from(crate::PROTOCOL_VERSION),
+            generations: Vec::new(),
+        }
+    }
+}
+
+impl ChangeFeed {
+    /// Adds the newest generation, keeping at most `keep` generations.
+    pub fn push(&mut self, changes: Changes, keep: usize) {
+        self.version = String::from(crate::PROTOCOL_VERSION);
+        self.generations.insert(0, changes);
+        self.generations.truncate(keep);
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(Error::JsonSerialize)
+    }
+
+    pub fn from_json(s: &str) -> Result<Self> {
+        serde_json::from_str(s).map_err(Error::JsonDeserialize)
+    }
+}
+
+/// Identity of an edge: two edges with the same key are the same link.
+fn edge_key(edge: &Edge) -> (&str, &str, &EdgeType, Option<&str>) {
+    (
+        &edge.source,
+        &edge.target,
+        &edge.edge_type,
+        edge.context.as_deref(),
+    )
+}
+
+impl PublicFile {
+    /// Computes the changes from `previous` to this file.
+    ///
+    /// Nodes are matched by URL, so a node whose title changed is renamed
+    /// rather than removed and added. Results follow the order of the files.
+    pub fn diff(&self, previous: &PublicFile) -> Changes {
+        let previous_titles: HashMap<&str, &str> = previous
+            .nodes
+            .iter()
+            .map(|node| (node.url.as_str(), node.title.as_str()))
+            .collect();
+        let urls: HashSet<&str> = self.nodes.iter().map(|node| node.url.as_str()).collect();
+
+        let mut added_nodes = Vec::new();
+        let mut renamed_nodes = Vec::new();
+        for node in &self.nodes {
+            match previous_titles.get(node.url.as_str()) {
+                None => added_nodes.push(node.clone()),
+                Some(title) if *title != node.title => renamed_nodes.push(Rename {
+                    url: node.url.clone(),
+                    from: (*title).to_owned(),
+                    to: node.title.clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+        let removed_nodes = previous
+            .nodes
+            .iter()
+            .filter(|node| !urls.contains(node.url.as_str()))
+            .map(|node| node.url.clone())
+            .collect();
+
+        let previous_edges: HashSet<_> = previous.edges.iter().map(edge_key).collect();
+        let edges: HashSet<_> = self.edges.iter().map(edge_key).collect();
+        let added_edges = self
+            .edges
+            .iter()
+            .filter(|edge| !previous_edges.contains(&edge_key(edge)))
+            .cloned()
+            .collect();
+        let removed_edges = previous
+            .edges
+            .iter()
+            .filter(|edge| !edges.contains(&edge_key(edge)))
+            .cloned()
+            .collect();
+
+        Changes {
+            from: previous.generated_at.clone(),
+            to: self.generated_at.clone(),
+            added_nodes,
+            removed_nodes,
+            renamed_nodes,
+            added_edges,
+            removed_edges,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Extensions, SiteMetadata};
+
+    fn node(url: &str, title: &str) -> Node {
+        Node {
+            url: String::from(url),
+            title: String::from(title),
+            group: None,
+            extensions: Extensions::new(),
+        }
+    }
+
+    fn edge(source: &str, target: &str) -> Edge {
+        Edge {
+            source: String::from(source),
+            target: String::from(target),
+            edge_type: EdgeType::Internal,
+            context: None,
+            extensions: Extensions::new(),
+        }
+    }
+
+    fn file(generated_at: &str, nodes: Vec<Node>, edges: Vec<Edge>) -> PublicFile {
+        PublicFile {
+            version: String::from(crate::PROTOCOL_VERSION),
+            generated_at: String::from(generated_at),
+            base_url: String::from("https://alice.dev/"),
+            site: SiteMetadata {
+                title: String::from("Alice's Garden"),
+                description: None,
+                language: None,
+                public_key: None,
+                extensions: Extensions::new(),
+            },
+            friends: Vec::new(),
+            nodes,
+            edges,
+            rings: Vec::new(),
+            shards: Vec::new(),
+            extensions: Extensions::new(),
+        }
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_renamed() {
+        let previous = file(
+            "2026-02-17T12:00:00Z",
+            vec![
+                node("/", "Home"),
+                node("/about", "About"),
+                node("/old", "Old"),
+            ],
+            vec![edge("/", "/about"), edge("/", "/old")],
+        );
+        let current = file(
+            "2026-02-18T12:00:00Z",
+            vec![
+                node("/", "Home"),
+                node("/about", "About me"),
+                node("/new", "New"),
+            ],
+            vec![edge("/", "/about"), edge("/", "/new")],
+        );
+
+        let changes = current.diff(&previous);
+
+        assert_eq!(changes.from, "2026-02-17T12:00:00Z");
+        assert_eq!(changes.to, "2026-02-18T12:00:00Z");
+        assert_eq!(changes.added_nodes, vec![node("/new", "New")]);
+        assert_eq!(changes.removed_nodes, vec![String::from("/old")]);
+        assert_eq!(
+            changes.renamed_nodes,
+            vec![Rename {
+                url: String::from("/about"),
+                from: String::from("About"),
+                to: String::from("About me"),
+            }]
+        );
+        assert_eq!(changes.added_edges, vec![edge("/", "/new")]);
+        assert_eq!(changes.removed_edges, vec![edge("/", "/old")]);
+    }
+
+    #[test]
+    fn diff_of_the_same_graph_is_empty() {
+        let previous = file("2026-02-17T12:00:00Z", vec![node("/", "Home")], Vec::new());
+        let mut current = previous.clone();
+        current.generated_at = String::from("2026-02-18T12:00:00Z");
+
+        assert!(current.diff(&previous).is_empty());
+    }
+
+    #[test]
+    fn feed_keeps_the_newest_generations() {
+        let mut feed = ChangeFeed::default();
+        for day in 1..=4 {
+            let previous = file(&format!("2026-02-0{day}T00:00:00Z"), Vec::new(), Vec::new());
+            let current = file(
+                &format!("2026-02-0{}T00:00:00Z", day + 1),
+                vec![node(&format!("/{day}"), "Page")],
+                Vec::new(),
+            );
+            feed.push(current.diff(&previous), 3);
+        }
+
+        let json = feed.to_json().unwrap();
+        let feed = ChangeFeed::from_json(&json).unwrap();
+        let to: Vec<&str> = feed.generations.iter().map(|c| c.to.as_str()).collect();
+        assert_eq!(
+            to,
+            vec![
+                "2026-02-05T00:00:00Z",
+                "2026-02-04T00:00:00Z",
+                "2026-02-03T00:00:00Z"
+            ]
+        );
+        assert!(!json.contains("removed_nodes"));
+    }
+}
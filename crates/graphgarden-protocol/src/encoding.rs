@@ -0,0 +1,277 @@
+//! Wire encodings of the public file.
+//!
+//! The compact encoding replaces edge `source` and `target` strings with
+//! indexes: `source` indexes `nodes`, and `target` indexes `nodes` followed by
+//! `targets`, a deduplicated table of the targets that are not nodes (friend
+//! URLs and broken internal links). An edge whose `source` is not a node,
+//! which [`PublicFile::validate`] reports, is indexed in `targets` as well.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+use crate::model::{Edge, EdgeType, Extensions, Friend, Node, PublicFile, SiteMetadata};
+use crate::ring::RingMembership;
+use crate::shard::ShardRef;
+
+/// How a public file is laid out, advertised in its `encoding` field.
+///
+/// Files without `encoding` are [`Encoding::Expanded`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Encoding {
+    /// Edges carry full URLs, as described by the specification example.
+    #[default]
+    Expanded,
+    /// Edges reference nodes and a deduplicated target table by index.
+    Compact,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CompactFile {
+    version: String,
+    generated_at: String,
+    base_url: String,
+    site: SiteMetadata,
+    friends: Vec<Friend>,
+    /// Read beforehand by [`take_encoding`], to pick the decoder.
+    #[serde(skip_deserializing)]
+    encoding: Encoding,
+    nodes: Vec<Node>,
+    #[serde(default)]
+    targets: Vec<String>,
+    edges: Vec<CompactEdge>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    rings: Vec<RingMembership>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    shards: Vec<ShardRef>,
+    #[serde(flatten)]
+    extensions: Extensions,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CompactEdge {
+    source: usize,
+    target: usize,
+    #[serde(rename = "type")]
+    edge_type: EdgeType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    context: Option<String>,
+    #[serde(flatten)]
+    extensions: Extensions,
+}
+
+/// Splits the `encoding` field off a raw file.
+pub(crate) fn take_encoding(file: &mut Value) -> Result<Encoding> {
+    let Some(raw) = file
+        .as_object_mut()
+        .and_then(|object| object.remove("encoding"))
+    else {
+        return Ok(Encoding::Expanded);
+    };
+    serde_json::from_value(raw.clone()).map_err(|_| Error::UnsupportedEncoding(raw.to_string()))
+}
+
+/// Serializes a file in the compact form.
+pub(crate) fn to_compact_json(file: &PublicFile) -> serde_json::Result<String> {
+    serde_json::to_string(&to_compact(file))
+}
+
+fn to_compact(file: &PublicFile) -> CompactFile {
+    // URLs are indexed in order of first appearance: nodes, then other targets
+    let mut indexes: HashMap<&str, usize> = HashMap::with_capacity(file.nodes.len());
+    for (index, node) in file.nodes.iter().enumerate() {
+        indexes.entry(node.url.as_str()).or_insert(index);
+    }
+
+    let mut targets = Vec::new();
+    let mut edges = Vec::with_capacity(file.edges.len());
+    for edge in &file.edges {
+        edges.push(CompactEdge {
+            source: index_of(&edge.source, &mut indexes, &mut targets, file.nodes.len()),
+            target: index_of(&edge.target, &mut indexes, &mut targets, file.nodes.len()),
+            edge_type: edge.edge_type.clone(),
+            context: edge.context.clone(),
+            extensions: edge.extensions.clone(),
+        });
+    }
+
+    CompactFile {
+        version: file.version.clone(),
+        generated_at: file.generated_at.clone(),
+        base_url: file.base_url.clone(),
+        site: file.site.clone(),
+        friends: file.friends.clone(),
+        encoding: Encoding::Compact,
+        nodes: file.nodes.clone(),
+        targets,
+        edges,
+        rings: file.rings.clone(),
+        shards: file.shards.clone(),
+        extensions: file.extensions.clone(),
+    }
+}
+
+/// Returns the index of `url`, appending it to `targets` when not indexed yet.
+fn index_of<'a>(
+    url: &'a str,
+    indexes: &mut HashMap<&'a str, usize>,
+    targets: &mut Vec<String>,
+    node_count: usize,
+) -> usize {
+    *indexes.entry(url).or_insert_with(|| {
+        targets.push(url.to_owned());
+        node_count + targets.len() - 1
+    })
+}
+
+/// Decodes a raw file in the compact form, with its `encoding` field removed.
+pub(crate) fn from_compact(file: Value) -> Result<PublicFile> {
+    let compact: CompactFile = serde_json::from_value(file).map_err(Error::JsonDeserialize)?;
+
+    let url_at = |index: usize, edge: usize| -> Result<String> {
+        let url = match index.checked_sub(compact.nodes.len()) {
+            None => Some(&compact.nodes[index].url),
+            Some(target) => compact.targets.get(target),
+        };
+        url.cloned().ok_or(Error::InvalidCompactEdge(edge))
+    };
+
+    let edges = compact
+        .edges
+        .iter()
+        .enumerate()
+        .map(|(index, edge)| {
+            Ok(Edge {
+                source: url_at(edge.source, index)?,
+                target: url_at(edge.target, index)?,
+                edge_type: edge.edge_type.clone(),
+                context: edge.context.clone(),
+                extensions: edge.extensions.clone(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(PublicFile {
+        version: compact.version,
+        generated_at: compact.generated_at,
+        base_url: compact.base_url,
+        site: compact.site,
+        friends: compact.friends,
+        nodes: compact.nodes,
+        edges,
+        rings: compact.rings,
+        shards: compact.shards,
+        extensions: compact.extensions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge(source: &str, target: &str, edge_type: EdgeType) -> Edge {
+        Edge {
+            source: String::from(source),
+            target: String::from(target),
+            edge_type,
+            context: None,
+            extensions: Extensions::new(),
+        }
+    }
+
+    fn sample_file() -> PublicFile {
+        let node = |url: &str| Node {
+            url: String::from(url),
+            title: String::from("Page"),
+            group: None,
+            extensions: Extensions::new(),
+        };
+        PublicFile {
+            version: String::from(crate::PROTOCOL_VERSION),
+            generated_at: String::from("2026-02-17T12:00:00Z"),
+            base_url: String::from("https://alice.dev/"),
+            site: SiteMetadata {
+                title: String::from("Alice's Garden"),
+                description: None,
+                language: None,
+                public_key: None,
+                extensions: Extensions::new(),
+            },
+            friends: vec![Friend::from("https://bob.dev/")],
+            nodes: vec![node("/"), node("/about")],
+            edges: vec![
+                edge("/", "/about", EdgeType::Internal),
+                edge("/", "https://bob.dev/", EdgeType::Friend),
+                edge("/about", "https://bob.dev/", EdgeType::Friend),
+                edge("/about", "/missing", EdgeType::Internal),
+            ],
+            rings: Vec::new(),
+            shards: Vec::new(),
+            extensions: Extensions::new(),
+        }
+    }
+
+    #[test]
+    fn compact_form_indexes_edges_and_deduplicates_targets() {
+        let json = sample_file().to_json_with(Encoding::Compact).unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["encoding"], "compact");
+        assert_eq!(
+            value["targets"],
+            serde_json::json!(["https://bob.dev/", "/missing"])
+        );
+        assert_eq!(
+            value["edges"],
+            serde_json::json!([
+                { "source": 0, "target": 1, "type": "internal" },
+                { "source": 0, "target": 2, "type": "friend" },
+                { "source": 1, "target": 2, "type": "friend" },
+                { "source": 1, "target": 3, "type": "internal" }
+            ])
+        );
+    }
+
+    #[test]
+    fn compact_form_round_trips() {
+        let original = sample_file();
+        let json = original.to_json_with(Encoding::Compact).unwrap();
+        let restored = PublicFile::from_json(&json).expect("compact file should decode");
+
+        assert_eq!(restored, original);
+    }
+
+    #[test]
+    fn expanded_form_does_not_advertise_encoding() {
+        let json = sample_file().to_json_with(Encoding::Expanded).unwrap();
+        assert!(!json.contains("encoding"));
+
+        let explicit = json.replacen('{', r#"{"encoding": "expanded","#, 1);
+        let restored = PublicFile::from_json(&explicit).unwrap();
+        assert!(restored.extensions.is_empty());
+    }
+
+    #[test]
+    fn rejects_unknown_encodings() {
+        let json = sample_file()
+            .to_json()
+            .unwrap()
+            .replacen('{', r#"{"encoding": "binary","#, 1);
+
+        let result = PublicFile::from_json(&json);
+        assert!(matches!(result, Err(Error::UnsupportedEncoding(_))));
+    }
+
+    #[test]
+    fn rejects_out_of_range_indexes() {
+        let json = sample_file().to_json_with(Encoding::Compact).unwrap();
+        let mut value: Value = serde_json::from_str(&json).unwrap();
+        value["edges"][2]["target"] = serde_json::json!(42);
+
+        let result = PublicFile::from_json(&value.to_string());
+        assert!(matches!(result, Err(Error::InvalidCompactEdge(2))));
+    }
+}
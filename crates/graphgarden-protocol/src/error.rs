@@ -0,0 +1,29 @@
+/// Errors that can occur when reading or writing protocol files.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("failed to serialize JSON: {0}")]
+    JsonSerialize(#[source] serde_json::Error),
+
+    #[error("failed to deserialize JSON: {0}")]
+    JsonDeserialize(#[source] serde_json::Error),
+
+    #[error("invalid protocol version '{0}': {1}")]
+    InvalidVersion(String, #[source] semver::Error),
+
+    #[error("unsupported encoding {0}")]
+    UnsupportedEncoding(String),
+
+    #[error("compact edge {0} references a missing node or target")]
+    InvalidCompactEdge(usize),
+
+    #[error("shard mismatch: {0}")]
+    ShardMismatch(String),
+
+    #[error("shard path '{0}' is not a relative path under graphgarden/")]
+    InvalidShardPath(String),
+
+    #[error("unsupported protocol version {found}, this implementation reads up to {current}")]
+    UnsupportedVersion { found: String, current: String },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -2,6 +2,29 @@
 //!
 //! Specification for the [GraphGarden protocol](https://github.com/bruits/graphgarden).
 //! See the [README](https://github.com/bruits/graphgarden/blob/main/crates/graphgarden-protocol/README.md) for the full specification.
+//!
+//! Also provides the serde model of the public file, so generators and
+//! visualizers can depend on the specification alone.
+
+pub mod canonical;
+pub mod changes;
+pub mod encoding;
+pub mod error;
+pub mod merged;
+pub mod model;
+pub mod ring;
+pub mod shard;
+pub mod validate;
+pub mod version;
+
+pub use changes::{ChangeFeed, Changes, Rename};
+pub use encoding::Encoding;
+pub use error::{Error, Result};
+pub use merged::{MergedEdge, MergedGraph, MergedNode, MergedSite};
+pub use model::{Edge, EdgeType, Extensions, Friend, Node, PublicFile, SiteMetadata};
+pub use ring::{Ring, RingMember, RingMembership, RingNavigation, RingViolation};
+pub use shard::{Shard, ShardBy, ShardRef};
+pub use validate::Violation;
 
 /// The protocol version, derived from this crate's version.
 pub const PROTOCOL_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,108 @@
+//! Merged graph: a site's graph stitched together with its friends' at build time.
+//!
+//! Unlike a public file, every URL is absolute, so pages of several sites can
+//! live in one file that a visualizer embeds without fetching the friends.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::model::{EdgeType, SiteMetadata};
+
+/// The graphs of a site and of its friends, in one file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergedGraph {
+    /// Protocol version the file was generated for.
+    pub version: String,
+    /// ISO 8601 UTC timestamp, e.g. `2026-02-17T12:00:00Z`.
+    pub generated_at: String,
+    /// Base URL of the site the graph was merged for.
+    pub base_url: String,
+    /// The merged sites, the site itself first.
+    pub sites: Vec<MergedSite>,
+    /// Pages declared by the merged sites, each listed once.
+    pub nodes: Vec<MergedNode>,
+    /// Links of the merged sites. Targets missing from `nodes` are frontier pages.
+    pub edges: Vec<MergedEdge>,
+}
+
+/// A site whose graph is part of a [`MergedGraph`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergedSite {
+    pub base_url: String,
+    pub site: SiteMetadata,
+}
+
+/// A page of a [`MergedGraph`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergedNode {
+    /// Absolute URL of the page.
+    pub url: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Base URL of the site declaring the page, one of the [`MergedSite`]s.
+    pub site: String,
+}
+
+/// A link of a [`MergedGraph`], between absolute URLs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergedEdge {
+    pub source: String,
+    pub target: String,
+    #[serde(rename = "type")]
+    pub edge_type: EdgeType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+}
+
+impl MergedGraph {
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(Error::JsonSerialize)
+    }
+
+    pub fn from_json(s: &str) -> Result<Self> {
+        serde_json::from_str(s).map_err(Error::JsonDeserialize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Extensions;
+
+    #[test]
+    fn round_trips_through_json() {
+        let graph = MergedGraph {
+            version: String::from("0.2.0"),
+            generated_at: String::from("2026-02-17T12:00:00Z"),
+            base_url: String::from("https://alice.dev/"),
+            sites: vec![MergedSite {
+                base_url: String::from("https://alice.dev/"),
+                site: SiteMetadata {
+                    title: String::from("Alice's Garden"),
+                    description: None,
+                    language: None,
+                    public_key: None,
+                    extensions: Extensions::new(),
+                },
+            }],
+            nodes: vec![MergedNode {
+                url: String::from("https://alice.dev/"),
+                title: String::from("Home"),
+                group: None,
+                site: String::from("https://alice.dev/"),
+            }],
+            edges: vec![MergedEdge {
+                source: String::from("https://alice.dev/"),
+                target: String::from("https://bob.dev/"),
+                edge_type: EdgeType::Friend,
+                context: None,
+            }],
+        };
+
+        let json = graph.to_json().unwrap();
+        assert!(json.contains(r#""type": "friend""#));
+        assert!(!json.contains("group"));
+        assert_eq!(MergedGraph::from_json(&json).unwrap(), graph);
+    }
+}
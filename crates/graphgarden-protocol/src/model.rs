@@ -0,0 +1,758 @@
+//! Serde model of the public file, see the [specification](https://github.com/bruits/graphgarden/blob/main/crates/graphgarden-protocol/README.md#public-file).
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::encoding::{self, Encoding};
+use crate::error::{Error, Result};
+use crate::ring::RingMembership;
+use crate::shard::ShardRef;
+use crate::version;
+
+/// Unknown fields of a model struct, keyed by name and sorted for stable output.
+pub type Extensions = BTreeMap<String, serde_json::Value>;
+
+/// A page of the site.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Node {
+    /// Path relative to the site's `base_url`, e.g. `/posts/hello`. Unique among nodes.
+    pub url: String,
+    pub title: String,
+    /// Kind of page (e.g. `post`, `tag`), an opaque label the producer assigns to it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Fields unknown to this version, such as vendor `x-*` extensions, kept through a round trip.
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+/// The `type` of an edge.
+///
+/// Any value other than `internal` and `friend` is kept as [`EdgeType::Custom`],
+/// so files using custom types (e.g. `related`, `citation`) round-trip unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum EdgeType {
+    Internal,
+    Friend,
+    Custom(String),
+}
+
+impl EdgeType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            EdgeType::Internal => "internal",
+            EdgeType::Friend => "friend",
+            EdgeType::Custom(name) => name,
+        }
+    }
+}
+
+impl From<String> for EdgeType {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "internal" => EdgeType::Internal,
+            "friend" => EdgeType::Friend,
+            _ => EdgeType::Custom(value),
+        }
+    }
+}
+
+impl From<EdgeType> for String {
+    fn from(value: EdgeType) -> Self {
+        match value {
+            EdgeType::Custom(name) => name,
+            other => other.as_str().to_owned(),
+        }
+    }
+}
+
+// Serialized through `String`, so the schema is written by hand rather than derived
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for EdgeType {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        std::borrow::Cow::Borrowed("EdgeType")
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "`internal` (same site), `friend` (declared friend site), or a custom type such as `related`. Consumers must accept unknown types.",
+            "type": "string",
+            "minLength": 1,
+            "examples": ["internal", "friend"]
+        })
+    }
+}
+
+impl std::fmt::Display for EdgeType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A link from one page of the site to another page, or to a friend.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Edge {
+    /// Relative path of the linking page, matching a [`Node::url`].
+    pub source: String,
+    /// Relative path for [`EdgeType::Internal`], absolute URL for [`EdgeType::Friend`].
+    pub target: String,
+    #[serde(rename = "type")]
+    pub edge_type: EdgeType,
+    /// Region of the source page the link lives in (e.g. `nav`, `main`, `footer`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    /// Fields unknown to this version, such as vendor `x-*` extensions, kept through a round trip.
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SiteMetadata {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// BCP 47 language tag, e.g. `en`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Base64 ed25519 public key the file is signed with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    /// Fields unknown to this version, such as vendor `x-*` extensions, kept through a round trip.
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+/// A declared friend site.
+///
+/// Serialized as a bare URL string when only `url` is set, so files without
+/// rich friends stay readable by older consumers, and as an object otherwise.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "FriendRepr", into = "FriendRepr")]
+pub struct Friend {
+    /// Base URL of the friend site.
+    pub url: String,
+    /// Display name, used as a label until the friend's file is fetched.
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// Relationship to the friend, e.g. XFN values such as `friend met`.
+    pub relationship: Option<String>,
+    /// Location of the friend's public file, when not at `url` + `.well-known/graphgarden.json`.
+    pub public_file: Option<String>,
+    /// Base64 ed25519 public key the friend's file must be signed with.
+    pub public_key: Option<String>,
+    /// Fields unknown to this version, such as vendor `x-*` extensions, kept through a round trip.
+    pub extensions: Extensions,
+}
+
+impl Friend {
+    pub fn new(url: impl Into<String>) -> Self {
+        Friend {
+            url: url.into(),
+            name: None,
+            description: None,
+            relationship: None,
+            public_file: None,
+            public_key: None,
+            extensions: Extensions::new(),
+        }
+    }
+
+    /// Whether only `url` is set, i.e. the friend serializes as a bare string.
+    fn is_bare(&self) -> bool {
+        self.name.is_none()
+            && self.description.is_none()
+            && self.relationship.is_none()
+            && self.public_file.is_none()
+            && self.public_key.is_none()
+            && self.extensions.is_empty()
+    }
+}
+
+impl From<&str> for Friend {
+    fn from(url: &str) -> Self {
+        Friend::new(url)
+    }
+}
+
+impl From<String> for Friend {
+    fn from(url: String) -> Self {
+        Friend::new(url)
+    }
+}
+
+/// A friend site, as a bare base URL or as an object with details.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(untagged)]
+enum FriendRepr {
+    Url(String),
+    Object(FriendObject),
+}
+
+/// A friend site with details.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+struct FriendObject {
+    /// Base URL of the friend site.
+    url: String,
+    /// Display name, used as a label until the friend's file is fetched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// Relationship to the friend, e.g. XFN values such as `friend met`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    relationship: Option<String>,
+    /// Location of the friend's public file, when not at `url` + `.well-known/graphgarden.json`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    public_file: Option<String>,
+    /// Base64 ed25519 public key the friend's file must be signed with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    public_key: Option<String>,
+    #[serde(flatten)]
+    extensions: Extensions,
+}
+
+impl From<FriendRepr> for Friend {
+    fn from(repr: FriendRepr) -> Self {
+        match repr {
+            FriendRepr::Url(url) => Friend::new(url),
+            FriendRepr::Object(object) => Friend {
+                url: object.url,
+                name: object.name,
+                description: object.description,
+                relationship: object.relationship,
+                public_file: object.public_file,
+                public_key: object.public_key,
+                extensions: object.extensions,
+            },
+        }
+    }
+}
+
+impl From<Friend> for FriendRepr {
+    fn from(friend: Friend) -> Self {
+        if friend.is_bare() {
+            return FriendRepr::Url(friend.url);
+        }
+        FriendRepr::Object(FriendObject {
+            url: friend.url,
+            name: friend.name,
+            description: friend.description,
+            relationship: friend.relationship,
+            public_file: friend.public_file,
+            public_key: friend.public_key,
+            extensions: friend.extensions,
+        })
+    }
+}
+
+// Serialized through `FriendRepr`, so the schema is the one of its wire forms
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for Friend {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        std::borrow::Cow::Borrowed("Friend")
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        <FriendRepr as schemars::JsonSchema>::json_schema(generator)
+    }
+}
+
+/// The public file served at `/.well-known/graphgarden.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PublicFile {
+    /// Protocol version the file was generated for.
+    pub version: String,
+    /// ISO 8601 UTC timestamp, e.g. `2026-02-17T12:00:00Z`.
+    pub generated_at: String,
+    /// Canonical HTTP(S) site URL, with a trailing slash.
+    pub base_url: String,
+    pub site: SiteMetadata,
+    /// Declared friend sites. Missing in 0.1.0 files.
+    #[serde(default)]
+    pub friends: Vec<Friend>,
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+    /// Webrings the site belongs to.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rings: Vec<RingMembership>,
+    /// Shard files holding the nodes and edges, when the file is a sharded index.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shards: Vec<ShardRef>,
+    /// Fields unknown to this version, such as vendor `x-*` extensions, kept through a round trip.
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+impl PublicFile {
+    /// Generates the JSON Schema describing the public file, as pretty-printed JSON.
+    ///
+    /// Only available with the `schema` feature.
+    #[cfg(feature = "schema")]
+    pub fn json_schema() -> String {
+        let schema = schemars::schema_for!(PublicFile);
+        let mut json = serde_json::to_string_pretty(&schema)
+            .expect("a generated schema always serializes to JSON");
+        json.push('\n');
+        json
+    }
+
+    /// Whether both files describe the same site and graph, ignoring `generated_at`.
+    pub fn same_content(&self, other: &PublicFile) -> bool {
+        let PublicFile {
+            version,
+            generated_at: _,
+            base_url,
+            site,
+            friends,
+            nodes,
+            edges,
+            rings,
+            shards,
+            extensions,
+        } = self;
+
+        *version == other.version
+            && *base_url == other.base_url
+            && *site == other.site
+            && *friends == other.friends
+            && *nodes == other.nodes
+            && *edges == other.edges
+            && *rings == other.rings
+            && *shards == other.shards
+            && *extensions == other.extensions
+    }
+
+    /// Serializes the file in the [`Encoding::Expanded`] form.
+    pub fn to_json(&self) -> Result<String> {
+        self.to_json_with(Encoding::Expanded)
+    }
+
+    /// Serializes the file in the given form, advertised in its `encoding` field
+    /// unless expanded.
+    pub fn to_json_with(&self, encoding: Encoding) -> Result<String> {
+        match encoding {
+            Encoding::Expanded => serde_json::to_string_pretty(self),
+            Encoding::Compact => encoding::to_compact_json(self),
+        }
+        .map_err(Error::JsonSerialize)
+    }
+
+    /// Parses a public file in either [`Encoding`], migrating files from older
+    /// protocol versions.
+    ///
+    /// Migrations only upgrade the shape of the file, `version` keeps the
+    /// version the file declares.
+    ///
+    /// Fails with [`Error::UnsupportedVersion`] for files from an incompatible,
+    /// newer version, see [`version::is_supported`].
+    pub fn from_json(s: &str) -> Result<Self> {
+        let mut value: serde_json::Value =
+            serde_json::from_str(s).map_err(Error::JsonDeserialize)?;
+        version::migrate(&mut value)?;
+        match encoding::take_encoding(&mut value)? {
+            Encoding::Expanded => serde_json::from_value(value).map_err(Error::JsonDeserialize),
+            Encoding::Compact => encoding::from_compact(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PROTOCOL_VERSION;
+
+    fn sample_public_file() -> PublicFile {
+        PublicFile {
+            version: String::from(PROTOCOL_VERSION),
+            generated_at: String::from("2026-02-17T12:00:00Z"),
+            base_url: String::from("https://alice.dev/"),
+            site: SiteMetadata {
+                title: String::from("Alice's Garden"),
+                description: Some(String::from("A blog about gardening")),
+                language: Some(String::from("en")),
+                public_key: None,
+                extensions: Extensions::new(),
+            },
+            friends: vec![Friend::from("https://bob.dev/")],
+            nodes: vec![
+                Node {
+                    url: String::from("/"),
+                    title: String::from("Home"),
+                    group: Some(String::from("home")),
+                    extensions: Extensions::new(),
+                },
+                Node {
+                    url: String::from("/about"),
+                    title: String::from("About"),
+                    group: None,
+                    extensions: Extensions::new(),
+                },
+            ],
+            edges: vec![
+                Edge {
+                    source: String::from("/"),
+                    target: String::from("/about"),
+                    edge_type: EdgeType::Internal,
+                    context: Some(String::from("nav")),
+                    extensions: Extensions::new(),
+                },
+                Edge {
+                    source: String::from("/about"),
+                    target: String::from("https://bob.dev/"),
+                    edge_type: EdgeType::Friend,
+                    context: None,
+                    extensions: Extensions::new(),
+                },
+            ],
+            rings: Vec::new(),
+            shards: Vec::new(),
+            extensions: Extensions::new(),
+        }
+    }
+
+    #[test]
+    fn public_file_round_trip() {
+        let original = sample_public_file();
+        let json = original.to_json().expect("serialization should succeed");
+        let restored = PublicFile::from_json(&json).expect("deserialization should succeed");
+        assert_eq!(original, restored);
+    }
+
+    #[test]
+    fn friend_serializes_as_string_or_object() {
+        let bare = serde_json::to_value(Friend::from("https://bob.dev/")).unwrap();
+        assert_eq!(bare, serde_json::json!("https://bob.dev/"));
+
+        let rich = Friend {
+            name: Some(String::from("Bob")),
+            relationship: Some(String::from("friend met")),
+            ..Friend::from("https://bob.dev/")
+        };
+        let json = serde_json::to_value(&rich).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "url": "https://bob.dev/",
+                "name": "Bob",
+                "relationship": "friend met"
+            })
+        );
+        assert_eq!(serde_json::from_value::<Friend>(json).unwrap(), rich);
+    }
+
+    #[test]
+    fn friends_accept_mixed_forms() {
+        let friends: Vec<Friend> = serde_json::from_str(
+            r#"[
+                "https://bob.dev/",
+                {
+                    "url": "https://carol.dev/",
+                    "name": "Carol",
+                    "public_file": "https://carol.dev/graph.json",
+                    "x-ring-rank": 2
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(friends[0], Friend::from("https://bob.dev/"));
+        assert_eq!(friends[1].name.as_deref(), Some("Carol"));
+        assert_eq!(
+            friends[1].public_file.as_deref(),
+            Some("https://carol.dev/graph.json")
+        );
+        assert_eq!(friends[1].extensions["x-ring-rank"], 2);
+    }
+
+    #[test]
+    fn same_content_ignores_generated_at() {
+        let original = sample_public_file();
+        let mut regenerated = original.clone();
+        regenerated.generated_at = String::from("2026-03-01T08:30:00Z");
+        assert!(original.same_content(&regenerated));
+
+        regenerated.nodes.pop();
+        assert!(!original.same_content(&regenerated));
+    }
+
+    #[test]
+    fn edge_type_serialization() {
+        let internal_json =
+            serde_json::to_string(&EdgeType::Internal).expect("serialization should succeed");
+        assert_eq!(internal_json, r#""internal""#);
+
+        let friend_json =
+            serde_json::to_string(&EdgeType::Friend).expect("serialization should succeed");
+        assert_eq!(friend_json, r#""friend""#);
+    }
+
+    #[test]
+    fn custom_edge_type_round_trip() {
+        let json = serde_json::to_string(&EdgeType::Custom(String::from("related")))
+            .expect("serialization should succeed");
+        assert_eq!(json, r#""related""#);
+
+        let restored: EdgeType =
+            serde_json::from_str(&json).expect("deserialization should succeed");
+        assert_eq!(restored, EdgeType::Custom(String::from("related")));
+    }
+
+    #[test]
+    fn known_edge_types_do_not_deserialize_as_custom() {
+        let internal: EdgeType =
+            serde_json::from_str(r#""internal""#).expect("deserialization should succeed");
+        let friend: EdgeType =
+            serde_json::from_str(r#""friend""#).expect("deserialization should succeed");
+        assert_eq!(internal, EdgeType::Internal);
+        assert_eq!(friend, EdgeType::Friend);
+    }
+
+    #[test]
+    fn edge_type_field_serializes_as_type() {
+        let edge = Edge {
+            source: String::from("/"),
+            target: String::from("/about"),
+            edge_type: EdgeType::Internal,
+            context: None,
+            extensions: Extensions::new(),
+        };
+        let json = serde_json::to_string(&edge).expect("serialization should succeed");
+        assert!(json.contains(r#""type":"internal""#));
+        assert!(!json.contains("edge_type"));
+        assert!(!json.contains("context"));
+    }
+
+    #[test]
+    fn node_omits_missing_group() {
+        let node = Node {
+            url: String::from("/"),
+            title: String::from("Home"),
+            group: None,
+            extensions: Extensions::new(),
+        };
+        let json = serde_json::to_string(&node).expect("serialization should succeed");
+        assert!(!json.contains("group"));
+    }
+
+    #[test]
+    fn site_metadata_omits_none_fields() {
+        let metadata = SiteMetadata {
+            title: String::from("Test"),
+            description: None,
+            language: None,
+            public_key: None,
+            extensions: Extensions::new(),
+        };
+        let json = serde_json::to_string(&metadata).expect("serialization should succeed");
+        assert!(!json.contains("description"));
+        assert!(!json.contains("language"));
+    }
+
+    #[test]
+    fn protocol_public_file_example_deserializes() {
+        let json = r#"{
+            "version": "0.1.0",
+            "generated_at": "2026-02-17T12:00:00Z",
+            "base_url": "https://alice.dev/",
+            "site": {
+                "title": "Alice's Garden",
+                "description": "A blog about …",
+                "language": "en"
+            },
+            "friends": ["https://bob.dev/"],
+            "nodes": [
+                { "url": "/", "title": "Home" },
+                { "url": "/about", "title": "About" },
+                { "url": "/posts/hello", "title": "Hello World" }
+            ],
+            "edges": [
+                { "source": "/", "target": "/about", "type": "internal" },
+                { "source": "/", "target": "/posts/hello", "type": "internal" },
+                { "source": "/about", "target": "https://bob.dev/", "type": "friend" }
+            ]
+        }"#;
+
+        let public_file = PublicFile::from_json(json).expect("protocol example should deserialize");
+
+        assert_eq!(public_file.version, "0.1.0");
+        assert_eq!(public_file.base_url, "https://alice.dev/");
+        assert_eq!(public_file.site.title, "Alice's Garden");
+        assert_eq!(public_file.nodes.len(), 3);
+        assert_eq!(public_file.edges.len(), 3);
+        assert_eq!(public_file.edges[2].edge_type, EdgeType::Friend);
+    }
+
+    #[test]
+    fn public_file_0_1_0_without_friends_migrates_to_empty() {
+        let json = r#"{
+            "version": "0.1.0",
+            "generated_at": "2026-02-17T12:00:00Z",
+            "base_url": "https://alice.dev/",
+            "site": {
+                "title": "Alice's Garden",
+                "description": "A blog about …",
+                "language": "en"
+            },
+            "nodes": [
+                { "url": "/", "title": "Home" },
+                { "url": "/about", "title": "About" }
+            ],
+            "edges": [
+                { "source": "/", "target": "/about", "type": "internal" }
+            ]
+        }"#;
+
+        let public_file =
+            PublicFile::from_json(json).expect("0.1.0 file without friends should deserialize");
+
+        assert_eq!(public_file.friends, Vec::<Friend>::new());
+        assert_eq!(public_file.version, "0.1.0");
+    }
+
+    #[test]
+    fn from_json_rejects_future_major_versions() {
+        let json = r#"{
+            "version": "1.0.0",
+            "generated_at": "2026-02-17T12:00:00Z",
+            "base_url": "https://alice.dev/",
+            "site": { "title": "Test" },
+            "friends": [],
+            "nodes": [],
+            "edges": []
+        }"#;
+
+        let result = PublicFile::from_json(json);
+
+        assert!(
+            matches!(result, Err(Error::UnsupportedVersion { .. })),
+            "expected UnsupportedVersion error"
+        );
+    }
+
+    #[test]
+    fn unknown_fields_round_trip_as_extensions() {
+        let json = r#"{
+            "version": "0.2.0",
+            "generated_at": "2026-02-17T12:00:00Z",
+            "base_url": "https://alice.dev/",
+            "site": { "title": "Alice's Garden", "x-theme": { "accent": "green" } },
+            "friends": [],
+            "nodes": [{ "url": "/", "title": "Home", "x-weight": 3 }],
+            "edges": [
+                { "source": "/", "target": "/", "type": "internal", "x-rel": "self" }
+            ],
+            "x-generator": "other-tool"
+        }"#;
+
+        let public_file = PublicFile::from_json(json).expect("extensions should deserialize");
+
+        assert_eq!(
+            public_file.extensions["x-generator"],
+            serde_json::json!("other-tool")
+        );
+        assert_eq!(
+            public_file.site.extensions["x-theme"],
+            serde_json::json!({ "accent": "green" })
+        );
+        assert_eq!(public_file.nodes[0].extensions["x-weight"], 3);
+        assert_eq!(public_file.edges[0].extensions["x-rel"], "self");
+
+        let restored: serde_json::Value =
+            serde_json::from_str(&public_file.to_json().unwrap()).unwrap();
+        let original: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(restored, original);
+    }
+
+    #[test]
+    fn empty_extensions_are_not_serialized() {
+        let json = sample_public_file().to_json().unwrap();
+        assert!(!json.contains("extensions"));
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn committed_json_schema_is_up_to_date() {
+        let committed = include_str!("../graphgarden.schema.json");
+        assert_eq!(
+            committed,
+            PublicFile::json_schema(),
+            "graphgarden.schema.json is stale, regenerate it with `graphgarden schema > crates/graphgarden-protocol/graphgarden.schema.json`"
+        );
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn json_schema_describes_required_fields() {
+        let schema: serde_json::Value =
+            serde_json::from_str(&PublicFile::json_schema()).expect("schema should be valid JSON");
+
+        let required: Vec<&str> = schema["required"]
+            .as_array()
+            .expect("schema should list required fields")
+            .iter()
+            .map(|field| field.as_str().unwrap())
+            .collect();
+        assert_eq!(
+            required,
+            vec![
+                "version",
+                "generated_at",
+                "base_url",
+                "site",
+                "nodes",
+                "edges"
+            ]
+        );
+    }
+
+    #[test]
+    fn from_json_rejects_malformed_json() {
+        let result = PublicFile::from_json("{not json");
+
+        assert!(result.is_err());
+        assert!(
+            matches!(result.unwrap_err(), Error::JsonDeserialize(_)),
+            "expected JsonDeserialize error"
+        );
+    }
+
+    #[test]
+    fn from_json_rejects_missing_required_fields() {
+        let result = PublicFile::from_json(r#"{"version":"0.1.0"}"#);
+
+        assert!(result.is_err());
+        assert!(
+            matches!(result.unwrap_err(), Error::JsonDeserialize(_)),
+            "expected JsonDeserialize error for missing fields"
+        );
+    }
+
+    #[test]
+    fn from_json_rejects_wrong_field_types() {
+        let json = r#"{
+            "version": "0.1.0",
+            "generated_at": "2026-02-17T12:00:00Z",
+            "base_url": "https://alice.dev/",
+            "site": { "title": "Test" },
+            "friends": [],
+            "nodes": "not_an_array",
+            "edges": []
+        }"#;
+
+        let result = PublicFile::from_json(json);
+
+        assert!(result.is_err());
+        assert!(
+            matches!(result.unwrap_err(), Error::JsonDeserialize(_)),
+            "expected JsonDeserialize error for wrong field types"
+        );
+    }
+}
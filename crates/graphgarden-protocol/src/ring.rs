@@ -0,0 +1,285 @@
+//! Webring manifest: the ordered membership list of a ring.
+//!
+//! Members are linked in manifest order, the last one wrapping around to the
+//! first. Each member can declare its membership in its public file, see
+//! [`RingMembership`].
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::model::{Extensions, Friend};
+use crate::validate::is_absolute_http;
+
+/// A webring manifest, usually written as `ring.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ring {
+    pub name: String,
+    /// Home page of the ring.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Base URLs of the members moderating the ring.
+    #[serde(default)]
+    pub moderators: Vec<String>,
+    /// Members, in ring order.
+    pub members: Vec<RingMember>,
+}
+
+/// A member site of a ring.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RingMember {
+    /// Base URL of the member site, with a trailing slash.
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// A ring the site belongs to, as declared in its public file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct RingMembership {
+    /// Name of the ring.
+    pub name: String,
+    /// Home page of the ring.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Base URL of the previous member.
+    pub prev: String,
+    /// Base URL of the next member.
+    pub next: String,
+    /// Fields unknown to this version, such as vendor `x-*` extensions, kept through a round trip.
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+/// The neighbors of a member, published next to its ring navigation bar.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RingNavigation {
+    /// Name of the ring.
+    pub name: String,
+    /// Home page of the ring.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub prev: RingMember,
+    pub next: RingMember,
+}
+
+impl RingNavigation {
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(Error::JsonSerialize)
+    }
+
+    pub fn from_json(s: &str) -> Result<Self> {
+        serde_json::from_str(s).map_err(Error::JsonDeserialize)
+    }
+}
+
+/// A rule broken by a [`Ring`] manifest.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum RingViolation {
+    #[error("the ring has no members")]
+    NoMembers,
+
+    #[error("member `{0}` is not an absolute HTTP(S) URL")]
+    MemberNotAbsolute(String),
+
+    #[error("member `{0}` does not end with a trailing slash")]
+    MemberMissingTrailingSlash(String),
+
+    #[error("member `{0}` appears more than once")]
+    DuplicateMember(String),
+
+    #[error("moderator `{0}` is not a member")]
+    UnknownModerator(String),
+}
+
+impl Ring {
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(Error::JsonSerialize)
+    }
+
+    pub fn from_json(s: &str) -> Result<Self> {
+        serde_json::from_str(s).map_err(Error::JsonDeserialize)
+    }
+
+    /// Checks the manifest, returning every violation found.
+    pub fn validate(&self) -> Vec<RingViolation> {
+        let mut violations = Vec::new();
+
+        if self.members.is_empty() {
+            violations.push(RingViolation::NoMembers);
+        }
+
+        let mut seen = HashSet::new();
+        for member in &self.members {
+            if !is_absolute_http(&member.url) {
+                violations.push(RingViolation::MemberNotAbsolute(member.url.clone()));
+            } else if !member.url.ends_with('/') {
+                violations.push(RingViolation::MemberMissingTrailingSlash(
+                    member.url.clone(),
+                ));
+            }
+            if !seen.insert(member.url.as_str()) {
+                violations.push(RingViolation::DuplicateMember(member.url.clone()));
+            }
+        }
+
+        for moderator in &self.moderators {
+            if !seen.contains(moderator.as_str()) {
+                violations.push(RingViolation::UnknownModerator(moderator.clone()));
+            }
+        }
+
+        violations
+    }
+
+    fn position(&self, url: &str) -> Option<usize> {
+        self.members.iter().position(|member| member.url == url)
+    }
+
+    /// The previous and next members of `url`, wrapping around the ring.
+    pub fn neighbors(&self, url: &str) -> Option<(&RingMember, &RingMember)> {
+        let index = self.position(url)?;
+        let count = self.members.len();
+        Some((
+            &self.members[(index + count - 1) % count],
+            &self.members[(index + 1) % count],
+        ))
+    }
+
+    /// The membership to declare in the public file of member `url`.
+    pub fn membership(&self, url: &str) -> Option<RingMembership> {
+        let (prev, next) = self.neighbors(url)?;
+        Some(RingMembership {
+            name: self.name.clone(),
+            url: self.url.clone(),
+            prev: prev.url.clone(),
+            next: next.url.clone(),
+            extensions: Extensions::new(),
+        })
+    }
+
+    /// The navigation of member `url`, with the details of its neighbors.
+    pub fn navigation(&self, url: &str) -> Option<RingNavigation> {
+        let (prev, next) = self.neighbors(url)?;
+        Some(RingNavigation {
+            name: self.name.clone(),
+            url: self.url.clone(),
+            prev: prev.clone(),
+            next: next.clone(),
+        })
+    }
+
+    /// The `friends` of member `url`: every other member, in ring order starting after it.
+    pub fn friends_of(&self, url: &str) -> Option<Vec<Friend>> {
+        let index = self.position(url)?;
+        let count = self.members.len();
+        let friends = (1..count)
+            .map(|offset| &self.members[(index + offset) % count])
+            .map(|member| Friend {
+                name: member.name.clone(),
+                ..Friend::new(member.url.clone())
+            })
+            .collect();
+        Some(friends)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(url: &str, name: Option<&str>) -> RingMember {
+        RingMember {
+            url: String::from(url),
+            name: name.map(String::from),
+        }
+    }
+
+    fn sample_ring() -> Ring {
+        Ring {
+            name: String::from("Garden Ring"),
+            url: Some(String::from("https://ring.garden/")),
+            description: None,
+            moderators: vec![String::from("https://alice.dev/")],
+            members: vec![
+                member("https://alice.dev/", Some("Alice")),
+                member("https://bob.dev/", None),
+                member("https://carol.dev/", Some("Carol")),
+            ],
+        }
+    }
+
+    #[test]
+    fn valid_ring_has_no_violations() {
+        assert!(sample_ring().validate().is_empty());
+    }
+
+    #[test]
+    fn reports_every_violation() {
+        let mut ring = sample_ring();
+        ring.members.push(member("https://bob.dev/", None));
+        ring.members.push(member("https://dave.dev", None));
+        ring.members.push(member("dave.dev/", None));
+        ring.moderators.push(String::from("https://mallory.dev/"));
+
+        assert_eq!(
+            ring.validate(),
+            vec![
+                RingViolation::DuplicateMember(String::from("https://bob.dev/")),
+                RingViolation::MemberMissingTrailingSlash(String::from("https://dave.dev")),
+                RingViolation::MemberNotAbsolute(String::from("dave.dev/")),
+                RingViolation::UnknownModerator(String::from("https://mallory.dev/")),
+            ]
+        );
+
+        ring.members.clear();
+        assert!(ring.validate().contains(&RingViolation::NoMembers));
+    }
+
+    #[test]
+    fn neighbors_wrap_around() {
+        let ring = sample_ring();
+
+        let (prev, next) = ring.neighbors("https://alice.dev/").unwrap();
+        assert_eq!(prev.url, "https://carol.dev/");
+        assert_eq!(next.url, "https://bob.dev/");
+
+        let (prev, next) = ring.neighbors("https://carol.dev/").unwrap();
+        assert_eq!(prev.url, "https://bob.dev/");
+        assert_eq!(next.url, "https://alice.dev/");
+
+        assert!(ring.neighbors("https://mallory.dev/").is_none());
+    }
+
+    #[test]
+    fn membership_links_neighbors() {
+        let membership = sample_ring().membership("https://bob.dev/").unwrap();
+
+        assert_eq!(membership.name, "Garden Ring");
+        assert_eq!(membership.prev, "https://alice.dev/");
+        assert_eq!(membership.next, "https://carol.dev/");
+    }
+
+    #[test]
+    fn navigation_round_trips_with_neighbor_names() {
+        let navigation = sample_ring().navigation("https://bob.dev/").unwrap();
+
+        assert_eq!(navigation.prev, member("https://alice.dev/", Some("Alice")));
+        assert_eq!(navigation.next, member("https://carol.dev/", Some("Carol")));
+        let json = navigation.to_json().unwrap();
+        assert_eq!(RingNavigation::from_json(&json).unwrap(), navigation);
+    }
+
+    #[test]
+    fn friends_are_the_other_members() {
+        let friends = sample_ring().friends_of("https://bob.dev/").unwrap();
+
+        let urls: Vec<&str> = friends.iter().map(|f| f.url.as_str()).collect();
+        assert_eq!(urls, vec!["https://carol.dev/", "https://alice.dev/"]);
+        assert_eq!(friends[0].name.as_deref(), Some("Carol"));
+    }
+}
@@ -0,0 +1,361 @@
+//! Sharded layout of the public file, for sites too large for a single file.
+//!
+//! The index (the public file itself) keeps the site metadata and friends, and
+//! lists its [`ShardRef`]s instead of nodes and edges. Each shard file holds a
+//! part of the nodes, along with the edges whose source is one of them.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::model::{Edge, Node, PublicFile};
+
+/// Directory of the shard files, relative to the index file.
+pub const SHARD_DIR: &str = "graphgarden";
+
+/// How nodes are split into shards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShardBy {
+    /// One shard per first path segment, e.g. `/docs/` for `/docs/intro`.
+    /// Pages at the root of the site share the `/` shard.
+    Prefix,
+    /// Shards of at most this many nodes, in node order.
+    Size(usize),
+}
+
+/// A shard listed in the index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ShardRef {
+    /// Location of the shard file, relative to the index file URL.
+    pub path: String,
+    /// Path prefix shared by the nodes of the shard, for prefix sharding.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    /// Number of nodes in the shard.
+    pub nodes: usize,
+    /// Number of edges in the shard.
+    pub edges: usize,
+}
+
+/// A shard file: some nodes of the site, and the edges leaving them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shard {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+impl ShardRef {
+    /// Checks that [`path`](Self::path) stays in the shard directory, so that
+    /// readers never fetch another host nor read another local file.
+    ///
+    /// Fails with [`Error::InvalidShardPath`] unless the path is relative, under
+    /// [`SHARD_DIR`], and made of plain segments: no `.`, `..` nor percent-encoding.
+    pub fn checked_path(&self) -> Result<&str> {
+        if is_shard_path(&self.path) {
+            Ok(&self.path)
+        } else {
+            Err(Error::InvalidShardPath(self.path.clone()))
+        }
+    }
+}
+
+pub(crate) fn is_shard_path(path: &str) -> bool {
+    let Some(rest) = path
+        .strip_prefix(SHARD_DIR)
+        .and_then(|rest| rest.strip_prefix('/'))
+    else {
+        return false;
+    };
+
+    rest.split('/').all(|segment| {
+        !matches!(segment, "" | "." | "..")
+            && segment
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'.' | b'-' | b'_'))
+    })
+}
+
+impl Shard {
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(Error::JsonSerialize)
+    }
+
+    pub fn from_json(s: &str) -> Result<Self> {
+        serde_json::from_str(s).map_err(Error::JsonDeserialize)
+    }
+}
+
+/// First path segment of a node URL, e.g. `/docs/` for `/docs/intro`.
+fn path_prefix(url: &str) -> String {
+    let rest = url.strip_prefix('/').unwrap_or(url);
+    match rest.find('/') {
+        Some(end) => format!("/{}/", &rest[..end]),
+        None => String::from("/"),
+    }
+}
+
+impl PublicFile {
+    /// Splits the file into an index and its shards, in the order of [`PublicFile::shards`].
+    ///
+    /// Edges go to the shard of their source node. Edges whose source is not a
+    /// node, which [`PublicFile::validate`] reports, go to the first shard.
+    pub fn into_shards(mut self, by: ShardBy) -> (PublicFile, Vec<Shard>) {
+        let nodes = std::mem::take(&mut self.nodes);
+        let edges = std::mem::take(&mut self.edges);
+
+        let groups: Vec<(Option<String>, Vec<Node>)> = match by {
+            ShardBy::Prefix => {
+                let mut groups: BTreeMap<String, Vec<Node>> = BTreeMap::new();
+                for node in nodes {
+                    groups.entry(path_prefix(&node.url)).or_default().push(node);
+                }
+                groups
+                    .into_iter()
+                    .map(|(prefix, nodes)| (Some(prefix), nodes))
+                    .collect()
+            }
+            ShardBy::Size(size) => {
+                let mut groups = Vec::new();
+                let mut nodes = nodes.into_iter().peekable();
+                while nodes.peek().is_some() {
+                    groups.push((None, nodes.by_ref().take(size.max(1)).collect()));
+                }
+                groups
+            }
+        };
+
+        let mut shards: Vec<Shard> = groups
+            .iter()
+            .map(|(_, nodes)| Shard {
+                nodes: nodes.clone(),
+                edges: Vec::new(),
+            })
+            .collect();
+        if shards.is_empty() && !edges.is_empty() {
+            shards.push(Shard {
+                nodes: Vec::new(),
+                edges: Vec::new(),
+            });
+        }
+
+        let shard_of: HashMap<&str, usize> = groups
+            .iter()
+            .enumerate()
+            .flat_map(|(index, (_, nodes))| {
+                nodes.iter().map(move |node| (node.url.as_str(), index))
+            })
+            .collect();
+        for edge in edges {
+            let index = shard_of.get(edge.source.as_str()).copied().unwrap_or(0);
+            shards[index].edges.push(edge);
+        }
+
+        self.shards = shards
+            .iter()
+            .enumerate()
+            .map(|(index, shard)| ShardRef {
+                path: format!("{SHARD_DIR}/{index}.json"),
+                prefix: groups.get(index).and_then(|(prefix, _)| prefix.clone()),
+                nodes: shard.nodes.len(),
+                edges: shard.edges.len(),
+            })
+            .collect();
+
+        (self, shards)
+    }
+
+    /// Reassembles an index with its shards, given in the order of [`PublicFile::shards`].
+    ///
+    /// Fails with [`Error::ShardMismatch`] when a shard is missing or doesn't
+    /// match the counts listed in the index, and with [`Error::InvalidShardPath`]
+    /// when the index lists a shard outside [`SHARD_DIR`].
+    pub fn reassemble(mut self, shards: Vec<Shard>) -> Result<PublicFile> {
+        let refs = std::mem::take(&mut self.shards);
+        for shard_ref in &refs {
+            shard_ref.checked_path()?;
+        }
+        if refs.len() != shards.len() {
+            return Err(Error::ShardMismatch(format!(
+                "expected {} shards, got {}",
+                refs.len(),
+                shards.len()
+            )));
+        }
+
+        for (shard_ref, shard) in refs.iter().zip(shards) {
+            if shard.nodes.len() != shard_ref.nodes || shard.edges.len() != shard_ref.edges {
+                return Err(Error::ShardMismatch(format!(
+                    "{} does not match its node and edge counts",
+                    shard_ref.path
+                )));
+            }
+            self.nodes.extend(shard.nodes);
+            self.edges.extend(shard.edges);
+        }
+
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{EdgeType, Extensions, SiteMetadata};
+
+    fn node(url: &str) -> Node {
+        Node {
+            url: String::from(url),
+            title: String::from("Page"),
+            group: None,
+            extensions: Extensions::new(),
+        }
+    }
+
+    fn edge(source: &str, target: &str) -> Edge {
+        Edge {
+            source: String::from(source),
+            target: String::from(target),
+            edge_type: EdgeType::Internal,
+            context: None,
+            extensions: Extensions::new(),
+        }
+    }
+
+    fn sample_file() -> PublicFile {
+        PublicFile {
+            version: String::from(crate::PROTOCOL_VERSION),
+            generated_at: String::from("2026-02-17T12:00:00Z"),
+            base_url: String::from("https://docs.dev/"),
+            site: SiteMetadata {
+                title: String::from("Docs"),
+                description: None,
+                language: None,
+                public_key: None,
+                extensions: Extensions::new(),
+            },
+            friends: Vec::new(),
+            nodes: vec![
+                node("/"),
+                node("/docs/intro"),
+                node("/about"),
+                node("/docs/"),
+                node("/blog/hello"),
+            ],
+            edges: vec![
+                edge("/", "/docs/"),
+                edge("/docs/intro", "/docs/"),
+                edge("/blog/hello", "/docs/intro"),
+                edge("/docs/", "/"),
+            ],
+            rings: Vec::new(),
+            shards: Vec::new(),
+            extensions: Extensions::new(),
+        }
+    }
+
+    #[test]
+    fn path_prefix_is_the_first_segment() {
+        assert_eq!(path_prefix("/"), "/");
+        assert_eq!(path_prefix("/about"), "/");
+        assert_eq!(path_prefix("/docs/"), "/docs/");
+        assert_eq!(path_prefix("/docs/guide/intro"), "/docs/");
+        assert_eq!(path_prefix("docs/intro"), "/docs/");
+    }
+
+    #[test]
+    fn shard_paths_stay_in_the_shard_directory() {
+        assert!(is_shard_path("graphgarden/0.json"));
+        assert!(is_shard_path("graphgarden/docs/intro-1.json"));
+
+        for path in [
+            "/etc/passwd",
+            "../../secret.json",
+            "graphgarden/../../secret.json",
+            "graphgarden/%2e%2e/secret.json",
+            "graphgarden//0.json",
+            "graphgarden/",
+            "graphgarden0.json",
+            "https://evil.example/0.json",
+            "//evil.example/0.json",
+            "graphgarden\\..\\secret.json",
+        ] {
+            assert!(!is_shard_path(path), "{path} should be rejected");
+        }
+    }
+
+    #[test]
+    fn reassemble_rejects_paths_outside_the_shard_directory() {
+        let (mut index, shards) = sample_file().into_shards(ShardBy::Prefix);
+        index.shards[0].path = String::from("../../secret.json");
+
+        let result = index.reassemble(shards);
+        assert!(
+            matches!(result, Err(Error::InvalidShardPath(ref path)) if path == "../../secret.json")
+        );
+    }
+
+    #[test]
+    fn shards_by_prefix_with_edges_of_their_nodes() {
+        let (index, shards) = sample_file().into_shards(ShardBy::Prefix);
+
+        assert!(index.nodes.is_empty());
+        assert!(index.edges.is_empty());
+        let prefixes: Vec<_> = index.shards.iter().map(|s| s.prefix.as_deref()).collect();
+        assert_eq!(prefixes, vec![Some("/"), Some("/blog/"), Some("/docs/")]);
+        assert_eq!(index.shards[2].path, "graphgarden/2.json");
+
+        let docs = &shards[2];
+        let urls: Vec<&str> = docs.nodes.iter().map(|n| n.url.as_str()).collect();
+        assert_eq!(urls, vec!["/docs/intro", "/docs/"]);
+        assert_eq!(
+            docs.edges,
+            vec![edge("/docs/intro", "/docs/"), edge("/docs/", "/")]
+        );
+        assert_eq!(index.shards[2].nodes, 2);
+        assert_eq!(index.shards[2].edges, 2);
+    }
+
+    #[test]
+    fn shards_by_size() {
+        let (index, shards) = sample_file().into_shards(ShardBy::Size(2));
+
+        let counts: Vec<usize> = shards.iter().map(|s| s.nodes.len()).collect();
+        assert_eq!(counts, vec![2, 2, 1]);
+        assert!(index.shards.iter().all(|s| s.prefix.is_none()));
+    }
+
+    #[test]
+    fn reassemble_restores_every_node_and_edge() {
+        let original = sample_file();
+        let (index, shards) = original.clone().into_shards(ShardBy::Size(2));
+
+        let index = PublicFile::from_json(&index.to_json().unwrap()).unwrap();
+        let shards = shards
+            .iter()
+            .map(|shard| Shard::from_json(&shard.to_json().unwrap()).unwrap())
+            .collect();
+        let restored = index.reassemble(shards).unwrap();
+
+        assert_eq!(restored.nodes.len(), original.nodes.len());
+        assert_eq!(restored.edges.len(), original.edges.len());
+        assert!(restored.shards.is_empty());
+        for node in &original.nodes {
+            assert!(restored.nodes.contains(node));
+        }
+    }
+
+    #[test]
+    fn reassemble_rejects_mismatched_shards() {
+        let (index, mut shards) = sample_file().into_shards(ShardBy::Prefix);
+        shards[0].nodes.pop();
+
+        let result = index.clone().reassemble(shards.clone());
+        assert!(matches!(result, Err(Error::ShardMismatch(_))));
+
+        shards.pop();
+        let result = index.reassemble(shards);
+        assert!(matches!(result, Err(Error::ShardMismatch(_))));
+    }
+}
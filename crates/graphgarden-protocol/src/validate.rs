@@ -0,0 +1,259 @@
+//! Checks the rules of the specification that the JSON shape alone can't express.
+
+use std::collections::HashSet;
+
+use crate::model::{EdgeType, PublicFile};
+use crate::shard::is_shard_path;
+
+/// A rule of the specification broken by a [`PublicFile`].
+///
+/// Edge violations carry the index of the edge in [`PublicFile::edges`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Violation {
+    #[error("base_url `{0}` is not an absolute HTTP(S) URL")]
+    BaseUrlNotAbsolute(String),
+
+    #[error("base_url `{0}` does not end with a trailing slash")]
+    BaseUrlMissingTrailingSlash(String),
+
+    #[error("friend `{0}` is not an absolute HTTP(S) URL")]
+    FriendNotAbsolute(String),
+
+    #[error("node url `{0}` is not a relative path")]
+    NodeUrlNotRelative(String),
+
+    #[error("node url `{0}` appears more than once")]
+    DuplicateNode(String),
+
+    // Not named `source`, which thiserror reserves for the underlying error
+    #[error("edge {index}: source `{url}` does not match any node")]
+    UnknownSource { index: usize, url: String },
+
+    #[error("edge {index}: internal target `{target}` is not a relative path")]
+    InternalTargetNotRelative { index: usize, target: String },
+
+    #[error("edge {index}: friend target `{target}` is not an absolute HTTP(S) URL")]
+    FriendTargetNotAbsolute { index: usize, target: String },
+
+    #[error("shard path `{0}` is not a relative path under `graphgarden/`")]
+    ShardPathOutsideShardDir(String),
+}
+
+/// Whether `url` is a path on the same site, e.g. `/posts/hello`.
+fn is_relative_path(url: &str) -> bool {
+    url.starts_with('/') && !url.starts_with("//")
+}
+
+/// Whether `url` is an absolute `http` or `https` URL with a host.
+pub(crate) fn is_absolute_http(url: &str) -> bool {
+    ["http://", "https://"].iter().any(|scheme| {
+        url.get(..scheme.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme))
+            && url.len() > scheme.len()
+    })
+}
+
+impl PublicFile {
+    /// Checks the file against the specification, returning every violation found.
+    ///
+    /// An empty list means the file is valid. Custom edge types are accepted
+    /// with either a relative or an absolute target.
+    pub fn validate(&self) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        if !is_absolute_http(&self.base_url) {
+            violations.push(Violation::BaseUrlNotAbsolute(self.base_url.clone()));
+        } else if !self.base_url.ends_with('/') {
+            violations.push(Violation::BaseUrlMissingTrailingSlash(
+                self.base_url.clone(),
+            ));
+        }
+
+        for friend in &self.friends {
+            if !is_absolute_http(&friend.url) {
+                violations.push(Violation::FriendNotAbsolute(friend.url.clone()));
+            }
+        }
+
+        let mut node_urls = HashSet::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            if !is_relative_path(&node.url) {
+                violations.push(Violation::NodeUrlNotRelative(node.url.clone()));
+            }
+            if !node_urls.insert(node.url.as_str()) {
+                violations.push(Violation::DuplicateNode(node.url.clone()));
+            }
+        }
+
+        for (index, edge) in self.edges.iter().enumerate() {
+            if !node_urls.contains(edge.source.as_str()) {
+                violations.push(Violation::UnknownSource {
+                    index,
+                    url: edge.source.clone(),
+                });
+            }
+            match edge.edge_type {
+                EdgeType::Internal if !is_relative_path(&edge.target) => {
+                    violations.push(Violation::InternalTargetNotRelative {
+                        index,
+                        target: edge.target.clone(),
+                    });
+                }
+                EdgeType::Friend if !is_absolute_http(&edge.target) => {
+                    violations.push(Violation::FriendTargetNotAbsolute {
+                        index,
+                        target: edge.target.clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        for shard in &self.shards {
+            if !is_shard_path(&shard.path) {
+                violations.push(Violation::ShardPathOutsideShardDir(shard.path.clone()));
+            }
+        }
+
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Edge, Extensions, Friend, Node, SiteMetadata};
+    use crate::shard::ShardBy;
+
+    fn node(url: &str) -> Node {
+        Node {
+            url: String::from(url),
+            title: String::from("Page"),
+            group: None,
+            extensions: Extensions::new(),
+        }
+    }
+
+    fn edge(source: &str, target: &str, edge_type: EdgeType) -> Edge {
+        Edge {
+            source: String::from(source),
+            target: String::from(target),
+            edge_type,
+            context: None,
+            extensions: Extensions::new(),
+        }
+    }
+
+    fn valid_file() -> PublicFile {
+        PublicFile {
+            version: String::from("0.2.0"),
+            generated_at: String::from("2026-02-17T12:00:00Z"),
+            base_url: String::from("https://alice.dev/"),
+            site: SiteMetadata {
+                title: String::from("Alice's Garden"),
+                description: None,
+                language: None,
+                public_key: None,
+                extensions: Extensions::new(),
+            },
+            friends: vec![Friend::from("https://bob.dev/")],
+            nodes: vec![node("/"), node("/about")],
+            edges: vec![
+                edge("/", "/about", EdgeType::Internal),
+                edge("/about", "https://bob.dev/", EdgeType::Friend),
+                edge("/about", "/", EdgeType::Custom(String::from("related"))),
+            ],
+            rings: Vec::new(),
+            shards: Vec::new(),
+            extensions: Extensions::new(),
+        }
+    }
+
+    #[test]
+    fn valid_file_has_no_violations() {
+        assert_eq!(valid_file().validate(), Vec::new());
+    }
+
+    #[test]
+    fn reports_base_url_problems() {
+        let mut file = valid_file();
+        file.base_url = String::from("https://alice.dev");
+        assert_eq!(
+            file.validate(),
+            vec![Violation::BaseUrlMissingTrailingSlash(String::from(
+                "https://alice.dev"
+            ))]
+        );
+
+        file.base_url = String::from("alice.dev/");
+        assert_eq!(
+            file.validate(),
+            vec![Violation::BaseUrlNotAbsolute(String::from("alice.dev/"))]
+        );
+    }
+
+    #[test]
+    fn reports_invalid_and_duplicate_nodes() {
+        let mut file = valid_file();
+        file.nodes.push(node("/about"));
+        file.nodes.push(node("https://alice.dev/contact"));
+
+        assert_eq!(
+            file.validate(),
+            vec![
+                Violation::DuplicateNode(String::from("/about")),
+                Violation::NodeUrlNotRelative(String::from("https://alice.dev/contact")),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_edge_violations_with_their_index() {
+        let mut file = valid_file();
+        file.friends.push(Friend::from("bob.dev"));
+        file.edges = vec![
+            edge("/missing", "/about", EdgeType::Internal),
+            edge("/", "https://alice.dev/about", EdgeType::Internal),
+            edge("/", "/bob", EdgeType::Friend),
+        ];
+
+        assert_eq!(
+            file.validate(),
+            vec![
+                Violation::FriendNotAbsolute(String::from("bob.dev")),
+                Violation::UnknownSource {
+                    index: 0,
+                    url: String::from("/missing"),
+                },
+                Violation::InternalTargetNotRelative {
+                    index: 1,
+                    target: String::from("https://alice.dev/about"),
+                },
+                Violation::FriendTargetNotAbsolute {
+                    index: 2,
+                    target: String::from("/bob"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_shards_outside_the_shard_directory() {
+        let (mut index, _) = valid_file().into_shards(ShardBy::Size(1));
+        index.shards[0].path = String::from("https://evil.example/0.json");
+
+        assert_eq!(
+            index.validate(),
+            vec![Violation::ShardPathOutsideShardDir(String::from(
+                "https://evil.example/0.json"
+            ))]
+        );
+    }
+
+    #[test]
+    fn protocol_relative_urls_are_not_relative_paths() {
+        assert!(is_relative_path("/posts/hello"));
+        assert!(!is_relative_path("//bob.dev/"));
+        assert!(!is_relative_path("posts/hello"));
+    }
+}
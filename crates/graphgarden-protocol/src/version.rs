@@ -0,0 +1,160 @@
+//! Version negotiation, and migration of older files to the current model.
+
+use semver::Version;
+use serde_json::{Map, Value};
+
+use crate::PROTOCOL_VERSION;
+use crate::error::{Error, Result};
+
+/// An upgrade of a raw public file to the protocol version `to`.
+struct Migration {
+    to: &'static str,
+    apply: fn(&mut Map<String, Value>),
+}
+
+/// Migrations in ascending order of [`Migration::to`].
+const MIGRATIONS: &[Migration] = &[Migration {
+    to: "0.2.0",
+    apply: add_empty_friends,
+}];
+
+/// 0.2.0 added the list of declared friends.
+fn add_empty_friends(file: &mut Map<String, Value>) {
+    file.entry("friends")
+        .or_insert_with(|| Value::Array(Vec::new()));
+}
+
+/// The protocol version implemented by this crate, see [`PROTOCOL_VERSION`].
+pub fn current() -> Version {
+    Version::parse(PROTOCOL_VERSION).expect("the crate version is valid semver")
+}
+
+/// Parses the `version` field of a public file.
+pub fn parse(version: &str) -> Result<Version> {
+    Version::parse(version).map_err(|error| Error::InvalidVersion(version.to_owned(), error))
+}
+
+/// Whether a file of `version` can be read into the current model.
+///
+/// Older files are always readable through migrations. Newer files are only
+/// readable when semver-compatible with [`current`], i.e. same major version,
+/// or same minor version while the major version is `0`.
+pub fn is_supported(version: &Version) -> bool {
+    let current = current();
+    if *version <= current {
+        return true;
+    }
+    if current.major == 0 {
+        version.major == 0 && version.minor == current.minor
+    } else {
+        version.major == current.major
+    }
+}
+
+/// Upgrades a raw public file to the current model, in place.
+///
+/// Files older than [`current`] go through every pending migration, and keep
+/// the `version` they declare so consumers can tell them apart. Files without
+/// a string `version` are left untouched for deserialization to report.
+pub(crate) fn migrate(file: &mut Value) -> Result<()> {
+    let Some(object) = file.as_object_mut() else {
+        return Ok(());
+    };
+    let Some(raw) = object.get("version").and_then(Value::as_str) else {
+        return Ok(());
+    };
+
+    let version = parse(raw)?;
+    if !is_supported(&version) {
+        return Err(Error::UnsupportedVersion {
+            found: version.to_string(),
+            current: PROTOCOL_VERSION.to_owned(),
+        });
+    }
+
+    for migration in MIGRATIONS {
+        let to = Version::parse(migration.to).expect("migration versions are valid semver");
+        if version < to {
+            (migration.apply)(object);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(raw: &str) -> Version {
+        Version::parse(raw).unwrap()
+    }
+
+    #[test]
+    fn current_matches_protocol_version() {
+        assert_eq!(current().to_string(), PROTOCOL_VERSION);
+    }
+
+    #[test]
+    fn older_and_compatible_versions_are_supported() {
+        let current = current();
+        assert!(is_supported(&version("0.1.0")));
+        assert!(is_supported(&current));
+
+        let mut patch = current.clone();
+        patch.patch += 1;
+        assert!(is_supported(&patch));
+    }
+
+    #[test]
+    fn incompatible_future_versions_are_not_supported() {
+        let current = current();
+        assert!(!is_supported(&Version::new(current.major + 1, 0, 0)));
+        if current.major == 0 {
+            assert!(!is_supported(&Version::new(0, current.minor + 1, 0)));
+        }
+    }
+
+    #[test]
+    fn migrations_are_sorted() {
+        let versions: Vec<Version> = MIGRATIONS.iter().map(|m| version(m.to)).collect();
+        assert!(versions.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(versions.iter().all(|to| *to <= current()));
+    }
+
+    #[test]
+    fn migrate_upgrades_old_files() {
+        let mut file = serde_json::json!({ "version": "0.1.0" });
+        migrate(&mut file).expect("0.1.0 should migrate");
+
+        assert_eq!(file["version"], "0.1.0");
+        assert_eq!(file["friends"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn migrate_keeps_current_files_untouched() {
+        let mut file = serde_json::json!({ "version": PROTOCOL_VERSION });
+        migrate(&mut file).expect("current version should be accepted");
+
+        assert_eq!(file, serde_json::json!({ "version": PROTOCOL_VERSION }));
+    }
+
+    #[test]
+    fn migrate_rejects_future_major_versions() {
+        let mut file = serde_json::json!({ "version": "99.0.0" });
+        let result = migrate(&mut file);
+
+        assert!(matches!(
+            result,
+            Err(Error::UnsupportedVersion { ref found, .. }) if found == "99.0.0"
+        ));
+    }
+
+    #[test]
+    fn migrate_rejects_invalid_versions() {
+        let mut file = serde_json::json!({ "version": "latest" });
+        let result = migrate(&mut file);
+
+        assert!(matches!(result, Err(Error::InvalidVersion(_, _))));
+    }
+}
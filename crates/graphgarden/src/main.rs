@@ -1,8 +1,25 @@
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use clap::{Parser, Subcommand};
+use graphgarden_core::build::{IncrementalBuild, PageStamps, default_generated_at, stamp_pages};
+use graphgarden_core::cache::FriendCache;
+use graphgarden_core::config::{Config, OutputConfig};
+use graphgarden_core::init::{detect, render_config};
+use graphgarden_core::merge::{MERGED_PATH, merge};
+use graphgarden_core::model::{ChangeFeed, Changes, Friend, PublicFile, Ring, ShardRef};
+use graphgarden_core::output::{
+    change_feed_path, is_written, public_file_path, read_change_feed, read_public_file,
+    remove_change_feed, write_change_feed, write_sharded_public_file,
+};
+use graphgarden_core::serve::{DEFAULT_MAX_AGE, FriendMount, ServeOptions, Server};
+use graphgarden_core::signature::SigningKey;
+use graphgarden_core::{fetch, ring, signature};
+use jiff::Timestamp;
+use url::Url;
 
 #[derive(Parser)]
 #[command(
@@ -21,6 +38,142 @@ enum Command {
         /// Path to the configuration file
         #[arg(long, default_value = "graphgarden.toml")]
         config: PathBuf,
+        /// Fixed `generated_at` timestamp (RFC 3339), overriding `SOURCE_DATE_EPOCH`
+        #[arg(long)]
+        timestamp: Option<Timestamp>,
+        /// Leave the existing file untouched when the graph has not changed
+        #[arg(long, conflicts_with = "watch")]
+        skip_unchanged: bool,
+        /// Sign the protocol file with this key file, see `graphgarden keygen`
+        #[arg(long, value_name = "KEY")]
+        sign: Option<PathBuf>,
+        /// Keep running, and rebuild when the output directory or the config file change
+        #[arg(long)]
+        watch: bool,
+        /// How often --watch checks for changes, in milliseconds; each check reads the metadata of every page
+        #[arg(long, value_name = "MS", default_value_t = 300, requires = "watch")]
+        interval: u64,
+    },
+    /// Manage the local cache of friend files
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+    /// Print the effective `[parse]` settings, once the preset is expanded
+    Explain {
+        /// Path to the configuration file
+        #[arg(long, default_value = "graphgarden.toml")]
+        config: PathBuf,
+    },
+    /// Detect the site generator of the project and write a commented configuration file
+    Init {
+        /// Where to write the configuration file, in the project directory
+        #[arg(long, default_value = "graphgarden.toml")]
+        config: PathBuf,
+        /// Canonical URL of the site, with a trailing slash
+        #[arg(long)]
+        base_url: Option<String>,
+        /// Title of the site, the project directory name by default
+        #[arg(long)]
+        title: Option<String>,
+        /// Overwrite an existing configuration file
+        #[arg(long)]
+        force: bool,
+    },
+    /// Generate a key file to sign the protocol file with
+    Keygen {
+        /// Where to write the key file, which must be kept secret
+        #[arg(default_value = "graphgarden.key")]
+        path: PathBuf,
+    },
+    /// Check a webring manifest, or generate the friends of its members
+    Ring {
+        #[command(subcommand)]
+        command: RingCommand,
+    },
+    /// Stitch the graph of the site and its friends' graphs into one file
+    Merge {
+        /// Path to the configuration file
+        #[arg(long, default_value = "graphgarden.toml")]
+        config: PathBuf,
+        /// Read a friend's graph from its local build instead of fetching it, e.g. https://bob.dev/=../bob/dist
+        #[arg(long = "friend", value_name = "URL=DIR", value_parser = parse_friend_mount)]
+        friends: Vec<FriendMount>,
+        /// Where to write the merged graph, `graphgarden-merged.json` in the output directory by default
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Fixed `generated_at` timestamp (RFC 3339), overriding `SOURCE_DATE_EPOCH`
+        #[arg(long)]
+        timestamp: Option<Timestamp>,
+        /// Fetch every friend from its origin, bypassing the cache
+        #[arg(long)]
+        no_cache: bool,
+    },
+    /// Print the JSON Schema of the protocol file
+    Schema,
+    /// Serve the output directory locally, with the headers the protocol asks of hosts
+    Serve {
+        /// Path to the configuration file
+        #[arg(long, default_value = "graphgarden.toml")]
+        config: PathBuf,
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+        /// Port to listen on, 0 for any free port
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+        /// Serve a friend's site from a local directory, e.g. https://bob.dev/=../bob/dist
+        #[arg(long = "friend", value_name = "URL=DIR", value_parser = parse_friend_mount)]
+        friends: Vec<FriendMount>,
+        /// `Cache-Control` max age of the protocol files, in seconds
+        #[arg(long, value_name = "SECONDS", default_value_t = DEFAULT_MAX_AGE)]
+        max_age: u32,
+    },
+    /// Check a protocol file against the specification
+    Validate {
+        /// Path to a local file, or URL of a file or of a site
+        target: String,
+        /// Also check the signature of the file, against its declared public key
+        #[arg(long)]
+        verify: bool,
+        /// Check the signature against this base64 public key instead (implies --verify)
+        #[arg(long, value_name = "PUBLIC_KEY")]
+        key: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheCommand {
+    /// Remove the friend files not refreshed for a while
+    Prune {
+        /// Path to the configuration file
+        #[arg(long, default_value = "graphgarden.toml")]
+        config: PathBuf,
+        /// Remove the files not fetched nor revalidated for this many days
+        #[arg(long, default_value_t = 30)]
+        days: u64,
+        /// Remove every file
+        #[arg(long, conflicts_with = "days")]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum RingCommand {
+    /// Check a ring manifest
+    Validate {
+        /// Path to the ring manifest
+        #[arg(default_value = "ring.toml")]
+        manifest: PathBuf,
+    },
+    /// Print the `friends` of a member, to paste into its `graphgarden.toml`
+    Friends {
+        /// Path to the ring manifest
+        #[arg(default_value = "ring.toml")]
+        manifest: PathBuf,
+        /// Base URL of the member, or every member when omitted
+        #[arg(long, value_name = "URL")]
+        member: Option<String>,
     },
 }
 
@@ -28,36 +181,759 @@ fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Command::Build { config } => run_build(&config),
+        Command::Build {
+            config,
+            timestamp,
+            skip_unchanged,
+            sign,
+            watch: false,
+            ..
+        } => run_build(&config, timestamp, skip_unchanged, sign.as_deref()),
+        Command::Build {
+            config,
+            timestamp,
+            sign,
+            watch: true,
+            interval,
+            ..
+        } => run_watch(
+            &config,
+            timestamp,
+            sign.as_deref(),
+            Duration::from_millis(interval),
+        ),
+        Command::Cache {
+            command: CacheCommand::Prune { config, days, all },
+        } => run_cache_prune(&config, if all { 0 } else { days }),
+        Command::Explain { config } => run_explain(&config),
+        Command::Init {
+            config,
+            base_url,
+            title,
+            force,
+        } => run_init(&config, base_url.as_deref(), title, force),
+        Command::Keygen { path } => run_keygen(&path),
+        Command::Ring {
+            command: RingCommand::Validate { manifest },
+        } => run_ring_validate(&manifest),
+        Command::Ring {
+            command: RingCommand::Friends { manifest, member },
+        } => run_ring_friends(&manifest, member.as_deref()),
+        Command::Merge {
+            config,
+            friends,
+            output,
+            timestamp,
+            no_cache,
+        } => run_merge(&config, &friends, output, timestamp, no_cache),
+        Command::Schema => {
+            print!("{}", PublicFile::json_schema());
+            Ok(())
+        }
+        Command::Serve {
+            config,
+            host,
+            port,
+            friends,
+            max_age,
+        } => run_serve(&config, &format!("{host}:{port}"), friends, max_age),
+        Command::Validate {
+            target,
+            verify,
+            key,
+        } => run_validate(&target, verify || key.is_some(), key.as_deref()),
+    }
+}
+
+fn run_build(
+    config_path: &Path,
+    timestamp: Option<Timestamp>,
+    skip_unchanged: bool,
+    sign: Option<&Path>,
+) -> Result<()> {
+    let config = load_config(config_path)?;
+    let signing_key = load_signing_key(sign)?;
+
+    write_ring_navigation(&config)?;
+
+    let public_file = match timestamp {
+        Some(timestamp) => graphgarden_core::build::build_at(&config, timestamp),
+        None => graphgarden_core::build::build(&config),
+    }
+    .context("failed to build the graph from the output directory")?;
+
+    write_output(&config, public_file, signing_key.as_ref(), skip_unchanged)
+}
+
+/// Rebuilds whenever the pages or the config change, until interrupted.
+///
+/// Only the changed pages are extracted again, and changes are debounced:
+/// a rebuild waits until the output directory stops changing for `interval`.
+/// Changes are found by polling: every `interval`, the metadata of each page
+/// is read again, which costs one `stat` per page per round on large sites.
+fn run_watch(
+    config_path: &Path,
+    timestamp: Option<Timestamp>,
+    sign: Option<&Path>,
+    interval: Duration,
+) -> Result<()> {
+    let signing_key = load_signing_key(sign)?;
+    println!(
+        "watching {} and its output directory, press Ctrl+C to stop",
+        config_path.display()
+    );
+
+    loop {
+        let config_stamp = modified(config_path);
+        if let Err(error) = watch_config(config_path, timestamp, signing_key.as_ref(), interval) {
+            eprintln!("✘ {error:#}");
+            while modified(config_path) == config_stamp {
+                thread::sleep(interval);
+            }
+        }
+        println!("{} changed, reloading", config_path.display());
     }
 }
 
-fn run_build(config_path: &Path) -> Result<()> {
-    let config = graphgarden_core::config::Config::from_file(config_path).context(format!(
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Watches the pages with the current config, returning when the config file changes.
+fn watch_config(
+    config_path: &Path,
+    timestamp: Option<Timestamp>,
+    signing_key: Option<&SigningKey>,
+    interval: Duration,
+) -> Result<()> {
+    let config_stamp = modified(config_path);
+    let config = load_config(config_path)?;
+    let mut incremental = IncrementalBuild::new(&config)?;
+    let mut previous = None;
+    let mut built = PageStamps::default();
+    let mut last_error = None;
+
+    loop {
+        // Static site generators often wipe the output directory while building,
+        // so failing to walk it is retried on the next round
+        match settled_stamps(&config, &built, interval) {
+            Ok(Some(pending)) => {
+                match rebuild(
+                    &config,
+                    &mut incremental,
+                    pending.clone(),
+                    timestamp,
+                    signing_key,
+                    previous.as_ref(),
+                ) {
+                    Ok(public_file) => {
+                        previous = Some(public_file);
+                        // Ring navigation may fill placeholders, picked up on the next round
+                        built = pending;
+                        last_error = None;
+                    }
+                    // Left unbuilt, so the rebuild is retried on the next round
+                    Err(error) => {
+                        let error = format!("{error:#}");
+                        if last_error.as_ref() != Some(&error) {
+                            eprintln!("✘ {error}, retrying");
+                        }
+                        last_error = Some(error);
+                    }
+                }
+            }
+            Ok(None) => last_error = None,
+            Err(error) => {
+                let error = format!("{error:#}");
+                if last_error.as_ref() != Some(&error) {
+                    eprintln!("✘ {error}, retrying");
+                }
+                last_error = Some(error);
+            }
+        }
+
+        thread::sleep(interval);
+        if modified(config_path) != config_stamp {
+            return Ok(());
+        }
+    }
+}
+
+/// Waits for the pages to stop changing, returning their stamps unless they match `built`.
+fn settled_stamps(
+    config: &Config,
+    built: &PageStamps,
+    interval: Duration,
+) -> Result<Option<PageStamps>> {
+    let mut pending = stamp_pages(config)?;
+    if pending == *built {
+        return Ok(None);
+    }
+
+    loop {
+        thread::sleep(interval);
+        let next = stamp_pages(config)?;
+        if next == pending {
+            return Ok(Some(pending));
+        }
+        pending = next;
+    }
+}
+
+/// Extracts the changed pages again and writes the output, returning the new public file.
+fn rebuild(
+    config: &Config,
+    incremental: &mut IncrementalBuild,
+    stamps: PageStamps,
+    timestamp: Option<Timestamp>,
+    signing_key: Option<&SigningKey>,
+    previous: Option<&PublicFile>,
+) -> Result<PublicFile> {
+    write_ring_navigation(config)?;
+
+    let update = incremental
+        .update(stamps)
+        .context("failed to extract the changed pages")?;
+    let generated_at = match timestamp {
+        Some(timestamp) => timestamp,
+        None => default_generated_at()?,
+    };
+    let public_file = incremental.public_file(generated_at)?;
+
+    let summary = match previous {
+        Some(previous) => summarize(&public_file.diff(previous)),
+        None => format!(
+            "{} nodes, {} edges",
+            public_file.nodes.len(),
+            public_file.edges.len()
+        ),
+    };
+    println!(
+        "✔ rebuilt: {} page(s) extracted, {} removed; {summary}",
+        update.extracted.len(),
+        update.removed.len()
+    );
+
+    write_output(config, public_file.clone(), signing_key, true)?;
+    Ok(public_file)
+}
+
+/// A one-line summary of changes, e.g. `+2 −1 nodes, 1 renamed, +3 −0 edges`.
+fn summarize(changes: &Changes) -> String {
+    if changes.is_empty() {
+        return String::from("graph unchanged");
+    }
+    let mut summary = format!(
+        "+{} −{} nodes",
+        changes.added_nodes.len(),
+        changes.removed_nodes.len()
+    );
+    if !changes.renamed_nodes.is_empty() {
+        summary.push_str(&format!(", {} renamed", changes.renamed_nodes.len()));
+    }
+    summary.push_str(&format!(
+        ", +{} −{} edges",
+        changes.added_edges.len(),
+        changes.removed_edges.len()
+    ));
+    summary
+}
+
+fn load_config(config_path: &Path) -> Result<Config> {
+    let config = Config::from_file(config_path).context(format!(
         "failed to load config from {}",
         config_path.display()
     ))?;
 
     config.validate().context("config validation failed")?;
+    Ok(config)
+}
+
+fn load_signing_key(sign: Option<&Path>) -> Result<Option<SigningKey>> {
+    sign.map(|path| {
+        signature::read_signing_key(path).context(format!(
+            "failed to load the signing key from {}",
+            path.display()
+        ))
+    })
+    .transpose()
+}
+
+// Before building, so the links of the filled placeholders are in the graph
+fn write_ring_navigation(config: &Config) -> Result<()> {
+    let ring_files =
+        ring::write_ring_navigation(config).context("failed to write the ring navigation")?;
+    for path in ring_files {
+        println!("✔ wrote {}", path.display());
+    }
+    Ok(())
+}
 
-    let output_dir = PathBuf::from(&config.output.dir);
+/// Signs, shards and writes a built public file, and records its changes.
+fn write_output(
+    config: &Config,
+    mut public_file: PublicFile,
+    signing_key: Option<&SigningKey>,
+    skip_unchanged: bool,
+) -> Result<()> {
+    if let Some(key) = signing_key {
+        public_file.site.public_key = Some(signature::public_key(key));
+    }
 
-    let public_file = graphgarden_core::build::build(&config)
-        .context("failed to build the graph from the output directory")?;
+    let output_dir = Path::new(&config.output.dir);
+    let destination = public_file_path(output_dir);
 
-    let json = public_file
-        .to_json()
+    if skip_unchanged && is_unchanged(config, &destination, &public_file, signing_key)? {
+        println!("✔ {} is up to date", destination.display());
+        return Ok(());
+    }
+
+    // Read before the previous file is overwritten
+    let changes = match config.output.changes {
+        Some(_) => read_public_file(&destination)
+            .ok()
+            .map(|previous| public_file.diff(&previous)),
+        None => None,
+    };
+
+    let serialized = serialize(config, public_file, signing_key)?;
+    write_sharded_public_file(
+        &config.output,
+        &serialized.index,
+        &serialized.shards,
+        serialized.signature.as_deref(),
+    )
+    .context("failed to write the public file")?;
+    println!("✔ wrote {}", destination.display());
+
+    // Only once the public file is written, so failed builds never record their changes
+    match config.output.changes {
+        Some(keep) => {
+            if let Some(changes) = changes.filter(|changes| !changes.is_empty()) {
+                record_changes(&config.output, changes, keep)?;
+            }
+        }
+        None => remove_change_feed(output_dir).context("failed to remove the change feed")?,
+    }
+
+    Ok(())
+}
+
+/// A public file as written to disk: its index, shards and signature.
+struct Serialized {
+    index: String,
+    shards: Vec<(ShardRef, String)>,
+    signature: Option<String>,
+}
+
+/// Signs, shards and serializes a public file in the configured encoding.
+fn serialize(
+    config: &Config,
+    public_file: PublicFile,
+    signing_key: Option<&SigningKey>,
+) -> Result<Serialized> {
+    let signature = signing_key
+        .map(|key| signature::sign_public_file(&public_file, key))
+        .transpose()
+        .context("failed to sign the public file")?;
+
+    let (index, shards) = match config.output.shard {
+        Some(by) => public_file.into_shards(by),
+        None => (public_file, Vec::new()),
+    };
+
+    let json = index
+        .to_json_with(config.output.encoding)
         .context("failed to serialize the public file to JSON")?;
+    let shards = index
+        .shards
+        .iter()
+        .cloned()
+        .zip(&shards)
+        .map(|(shard_ref, shard)| shard.to_json().map(|json| (shard_ref, json)))
+        .collect::<Result<Vec<_>, _>>()
+        .context("failed to serialize the shards to JSON")?;
+
+    Ok(Serialized {
+        index: json,
+        shards,
+        signature,
+    })
+}
+
+/// Adds a generation of changes to the change feed, keeping the last `keep` ones.
+fn record_changes(output: &OutputConfig, changes: Changes, keep: usize) -> Result<()> {
+    let path = change_feed_path(Path::new(&output.dir));
+    let mut feed = read_change_feed(&path).unwrap_or_else(|error| {
+        eprintln!("⚠ {error}, starting a new change feed, previous generations are lost");
+        ChangeFeed::default()
+    });
+    feed.push(changes, keep);
+
+    let json = feed
+        .to_json()
+        .context("failed to serialize the change feed to JSON")?;
+    write_change_feed(output, &json).context("failed to write the change feed")?;
+    println!("✔ wrote {}", path.display());
+    Ok(())
+}
+
+/// Whether `destination` already holds the files this build would write, byte
+/// for byte, regardless of its `generated_at`.
+///
+/// Comparing bytes rather than graphs catches a changed encoding, sharding,
+/// compression level or signature.
+fn is_unchanged(
+    config: &Config,
+    destination: &Path,
+    public_file: &PublicFile,
+    signing_key: Option<&SigningKey>,
+) -> Result<bool> {
+    let Some(existing) = fs::read_to_string(destination)
+        .ok()
+        .and_then(|json| PublicFile::from_json(&json).ok())
+    else {
+        return Ok(false);
+    };
 
-    let well_known = output_dir.join(".well-known");
-    fs::create_dir_all(&well_known).context(format!(
-        "failed to create directory {}",
-        well_known.display()
+    let mut candidate = public_file.clone();
+    candidate.generated_at = existing.generated_at;
+    let serialized = serialize(config, candidate, signing_key)?;
+    is_written(
+        &config.output,
+        &serialized.index,
+        &serialized.shards,
+        serialized.signature.as_deref(),
+    )
+    .context("failed to compare with the existing public file")
+}
+
+fn run_explain(config_path: &Path) -> Result<()> {
+    let config = Config::from_file(config_path).context(format!(
+        "failed to load config from {}",
+        config_path.display()
     ))?;
 
-    let destination = well_known.join("graphgarden.json");
+    match config.parse.preset {
+        Some(preset) => println!(
+            "# {} with the {} preset ({})",
+            config_path.display(),
+            preset.key(),
+            preset.name()
+        ),
+        None => println!("# {} without preset", config_path.display()),
+    }
+    print!("{}", config.parse.effective().to_toml()?);
+    Ok(())
+}
+
+fn parse_friend_mount(input: &str) -> Result<FriendMount, String> {
+    FriendMount::parse(input).map_err(|error| error.to_string())
+}
+
+fn run_serve(
+    config_path: &Path,
+    address: &str,
+    friends: Vec<FriendMount>,
+    max_age: u32,
+) -> Result<()> {
+    let config = load_config(config_path)?;
+    let options = ServeOptions {
+        friends,
+        max_age,
+        ..ServeOptions::new(&config.output.dir)
+    };
+    let server = Server::bind(address, options.clone()).context("failed to start the server")?;
+    let origin = match server.local_addr() {
+        Some(address) => format!("http://{address}"),
+        None => format!("http://{address}"),
+    };
+
+    println!("serving {} at {origin}/", options.root.display());
+    for friend in &options.friends {
+        println!(
+            "  {} from {} at {origin}{}",
+            friend.url,
+            friend.dir.display(),
+            friend.path()
+        );
+    }
+    println!("press Ctrl+C to stop");
+
+    server.run(|served| println!("{} {} {}", served.status, served.method, served.url));
+    Ok(())
+}
+
+fn run_merge(
+    config_path: &Path,
+    mounts: &[FriendMount],
+    output: Option<PathBuf>,
+    timestamp: Option<Timestamp>,
+    no_cache: bool,
+) -> Result<()> {
+    let config = load_config(config_path)?;
+    let cache = (!no_cache).then(|| FriendCache::new(&config.cache.dir));
+    let output_dir = Path::new(&config.output.dir);
+    let own_path = public_file_path(output_dir);
+    let own = read_public_file(&own_path).context(format!(
+        "failed to read {}, run `graphgarden build` first",
+        own_path.display()
+    ))?;
+
+    let mut friends = Vec::new();
+    let mut mounted = Vec::new();
+    for friend in &config.friends {
+        let mount = mounts.iter().find(|mount| {
+            Url::parse(&friend.url).is_ok_and(|url| url.origin() == mount.url.origin())
+        });
+        let file = match mount {
+            Some(mount) => {
+                mounted.push(mount);
+                read_mounted_friend(mount)
+            }
+            None => fetch_friend(friend, cache.as_ref()),
+        };
+        match file {
+            Ok(file) => friends.push(file),
+            // Friends being down should not break the build, as in the web component
+            Err(error) => eprintln!("⚠ skipping {}: {error:#}", friend.url),
+        }
+    }
+    for mount in mounts.iter().filter(|mount| !mounted.contains(mount)) {
+        friends.push(read_mounted_friend(mount)?);
+    }
+
+    let generated_at = match timestamp {
+        Some(timestamp) => timestamp,
+        None => default_generated_at()?,
+    };
+    let graph = merge(&own, &friends, generated_at).context("failed to merge the graphs")?;
+    let json = graph
+        .to_json()
+        .context("failed to serialize the merged graph to JSON")?;
+
+    let destination = output.unwrap_or_else(|| output_dir.join(MERGED_PATH));
     fs::write(&destination, json).context(format!("failed to write {}", destination.display()))?;
+    println!(
+        "✔ wrote {}: {} sites, {} nodes, {} edges",
+        destination.display(),
+        graph.sites.len(),
+        graph.nodes.len(),
+        graph.edges.len()
+    );
+    Ok(())
+}
 
-    println!("✔ wrote {}", destination.display());
+fn read_mounted_friend(mount: &FriendMount) -> Result<PublicFile> {
+    let path = public_file_path(&mount.dir);
+    read_public_file(&path).context(format!("failed to read {}", path.display()))
+}
+
+/// Fetches the public file of a friend, verifying its signature when its key is pinned.
+///
+/// Through the cache when given, warning about the copies it serves because
+/// the friend is unreachable.
+fn fetch_friend(friend: &Friend, cache: Option<&FriendCache>) -> Result<PublicFile> {
+    let location = friend.public_file.as_deref().unwrap_or(&friend.url);
+    let file = match (cache, &friend.public_key) {
+        (Some(cache), Some(_)) => cache.fetch_verified_public_file(friend),
+        (Some(cache), None) => cache.fetch_public_file(location),
+        (None, Some(_)) => fetch::fetch_verified_public_file(friend),
+        (None, None) => fetch::fetch_public_file(location),
+    };
+
+    for stale in cache.map(FriendCache::take_stale).unwrap_or_default() {
+        let fetched_at = Timestamp::try_from(stale.fetched_at)
+            .map(|timestamp| timestamp.to_string())
+            .unwrap_or_default();
+        eprintln!(
+            "⚠ {} is unreachable ({}), using the copy fetched at {fetched_at}",
+            stale.url, stale.error
+        );
+    }
+    file.context(format!("failed to fetch the graph of {}", friend.url))
+}
+
+fn run_cache_prune(config_path: &Path, days: u64) -> Result<()> {
+    // Only `[cache]` is needed, so the site doesn't have to be built nor valid
+    let config = Config::from_file(config_path).context(format!(
+        "failed to load config from {}",
+        config_path.display()
+    ))?;
+    let cache = FriendCache::new(&config.cache.dir);
+    let removed = cache
+        .prune(Duration::from_secs(days.saturating_mul(24 * 60 * 60)))
+        .context(format!("failed to prune {}", cache.dir().display()))?;
+    println!("✔ removed {removed} file(s) from {}", cache.dir().display());
+    Ok(())
+}
+
+/// Placeholder `base_url` written when `--base-url` is not set.
+const PLACEHOLDER_BASE_URL: &str = "https://example.com/";
+
+fn run_init(
+    config_path: &Path,
+    base_url: Option<&str>,
+    title: Option<String>,
+    force: bool,
+) -> Result<()> {
+    if config_path.exists() && !force {
+        bail!(
+            "{} already exists, pass --force to overwrite it",
+            config_path.display()
+        );
+    }
+
+    let project_dir = match config_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => std::env::current_dir().context("failed to read the current directory")?,
+    };
+    let preset = detect(&project_dir);
+    let title = title.unwrap_or_else(|| {
+        project_dir
+            .canonicalize()
+            .ok()
+            .and_then(|dir| {
+                dir.file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+            })
+            .unwrap_or_else(|| String::from("My Garden"))
+    });
+
+    // Relative to the working directory, where `build` resolves `output.dir`
+    let output_base = config_path.parent().unwrap_or(Path::new(""));
+    let toml = render_config(
+        preset,
+        output_base,
+        base_url.unwrap_or(PLACEHOLDER_BASE_URL),
+        &title,
+    )
+    .context("invalid --base-url")?;
+    fs::write(config_path, toml).context(format!("failed to write {}", config_path.display()))?;
+
+    match preset {
+        Some(preset) => println!("✔ detected a {} site", preset.name()),
+        None => println!("no known site generator detected, using generic defaults"),
+    }
+    println!("✔ wrote {}", config_path.display());
+    if base_url.is_none() {
+        println!("set site.base_url, or run again with --base-url");
+    }
+    Ok(())
+}
+
+fn run_keygen(path: &Path) -> Result<()> {
+    use std::io::Write;
+
+    let key = signature::generate_signing_key()?;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    // Only the owner may read the secret key
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = match options.open(path) {
+        Ok(file) => file,
+        Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => bail!(
+            "{} already exists, remove it first to replace the key",
+            path.display()
+        ),
+        Err(error) => {
+            return Err(error).context(format!("failed to create {}", path.display()));
+        }
+    };
+    writeln!(file, "{}", signature::encode_signing_key(&key))
+        .context(format!("failed to write {}", path.display()))?;
+
+    println!("✔ wrote {}, keep it secret", path.display());
+    println!("public key: {}", signature::public_key(&key));
+    Ok(())
+}
+
+fn load_valid_ring(manifest: &Path) -> Result<Ring> {
+    let ring =
+        ring::load_ring(manifest).context(format!("failed to load {}", manifest.display()))?;
+
+    let violations = ring.validate();
+    if !violations.is_empty() {
+        for violation in &violations {
+            eprintln!("✘ {violation}");
+        }
+        bail!(
+            "{} has {} violation(s)",
+            manifest.display(),
+            violations.len()
+        );
+    }
+    Ok(ring)
+}
+
+fn run_ring_validate(manifest: &Path) -> Result<()> {
+    let ring = load_valid_ring(manifest)?;
+    println!(
+        "✔ {} is valid, {} members in {}",
+        manifest.display(),
+        ring.members.len(),
+        ring.name
+    );
+    Ok(())
+}
+
+fn run_ring_friends(manifest: &Path, member: Option<&str>) -> Result<()> {
+    let ring = load_valid_ring(manifest)?;
+
+    let members: Vec<&str> = match member {
+        Some(url) => vec![url],
+        None => ring.members.iter().map(|m| m.url.as_str()).collect(),
+    };
+    for (index, url) in members.into_iter().enumerate() {
+        let friends = ring
+            .friends_of(url)
+            .with_context(|| format!("{url} is not a member of {}", ring.name))?;
+        if member.is_none() {
+            if index > 0 {
+                println!();
+            }
+            println!("# {url}");
+        }
+        print!("{}", ring::friends_toml(&friends)?);
+    }
+    Ok(())
+}
+
+fn run_validate(target: &str, verify: bool, key: Option<&str>) -> Result<()> {
+    let remote = target.starts_with("http://") || target.starts_with("https://");
+    let public_file = if remote {
+        graphgarden_core::fetch::fetch_public_file(target)
+            .context(format!("failed to fetch {target}"))?
+    } else {
+        read_public_file(Path::new(target)).context(format!("failed to read {target}"))?
+    };
+
+    let violations = public_file.validate();
+    if !violations.is_empty() {
+        for violation in &violations {
+            eprintln!("✘ {violation}");
+        }
+        bail!("{target} has {} violation(s)", violations.len());
+    }
+
+    if verify {
+        let file_signature = if remote {
+            graphgarden_core::fetch::fetch_signature(target)
+                .context(format!("failed to fetch the signature of {target}"))?
+        } else {
+            let path = format!("{target}.{}", signature::SIGNATURE_EXTENSION);
+            fs::read_to_string(&path).context(format!("failed to read {path}"))?
+        };
+        signature::verify_public_file(&public_file, &file_signature, key)
+            .context(format!("failed to verify the signature of {target}"))?;
+        println!("✔ {target} is valid and signed");
+        return Ok(());
+    }
+
+    println!("✔ {target} is valid");
     Ok(())
 }
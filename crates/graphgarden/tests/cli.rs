@@ -581,7 +581,7 @@ fn build_protocol_invariants() {
 
     let value = run_build_and_read_output(&config_path, &output_dir);
 
-    assert_eq!(value["version"], "0.1.0");
+    assert_eq!(value["version"], graphgarden_core::PROTOCOL_VERSION);
 
     let generated_at = value["generated_at"].as_str().unwrap();
     assert!(
@@ -693,7 +693,7 @@ fn build_empty_site() {
     assert!(edges.is_empty(), "empty site should have no edges");
 
     // JSON is still structurally valid with required top-level fields
-    assert_eq!(value["version"], "0.1.0");
+    assert_eq!(value["version"], graphgarden_core::PROTOCOL_VERSION);
     assert!(value["generated_at"].as_str().is_some());
     assert_eq!(value["base_url"], "https://test.dev/");
     assert_eq!(value["site"]["title"], "Test Site");
@@ -905,3 +905,1192 @@ fn build_rejects_invalid_css_selector() {
         .failure()
         .stderr(predicates::str::contains("invalid CSS selector"));
 }
+
+#[test]
+fn schema_prints_the_protocol_json_schema() {
+    let output = cargo_bin_cmd!("graphgarden")
+        .arg("schema")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let schema: serde_json::Value =
+        serde_json::from_slice(&output).expect("schema output should be valid JSON");
+    assert_eq!(schema["title"], "PublicFile");
+    assert!(schema["$defs"]["Edge"].is_object());
+}
+
+#[test]
+fn validate_accepts_built_output() {
+    let tmp = TempDir::new().unwrap();
+    let output_dir = tmp.path().join("dist");
+    fs::create_dir_all(&output_dir).unwrap();
+
+    write_file(
+        &output_dir,
+        "index.html",
+        "<html><head><title>Home</title></head><body><a href=\"/about\">About</a></body></html>",
+    );
+    write_file(
+        &output_dir,
+        "about.html",
+        "<html><head><title>About</title></head><body><a href=\"/\">Home</a></body></html>",
+    );
+
+    let config_path = tmp.path().join("graphgarden.toml");
+    fs::write(
+        &config_path,
+        minimal_config("https://test.dev/", output_dir.to_str().unwrap()),
+    )
+    .unwrap();
+    run_build_and_read_output(&config_path, &output_dir);
+
+    let public_file = output_dir.join(".well-known/graphgarden.json");
+    cargo_bin_cmd!("graphgarden")
+        .args(["validate", public_file.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("is valid"));
+}
+
+#[test]
+fn validate_reports_violations() {
+    let tmp = TempDir::new().unwrap();
+    let file_path = tmp.path().join("graphgarden.json");
+    fs::write(
+        &file_path,
+        r#"{
+  "version": "0.2.0",
+  "generated_at": "2026-02-17T12:00:00Z",
+  "base_url": "https://test.dev",
+  "site": { "title": "Test Site" },
+  "nodes": [{ "url": "/", "title": "Home" }],
+  "edges": [{ "source": "/missing", "target": "/", "type": "internal" }]
+}"#,
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("graphgarden")
+        .args(["validate", file_path.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("trailing slash"))
+        .stderr(predicates::str::contains(
+            "edge 0: source `/missing` does not match any node",
+        ))
+        .stderr(predicates::str::contains("2 violation(s)"));
+}
+
+fn write_two_page_site(output_dir: &Path) {
+    write_file(
+        output_dir,
+        "index.html",
+        "<html><head><title>Home</title></head><body><a href=\"/about\">About</a></body></html>",
+    );
+    write_file(
+        output_dir,
+        "about.html",
+        "<html><head><title>About</title></head><body><a href=\"/\">Home</a></body></html>",
+    );
+}
+
+#[test]
+fn build_honors_timestamp_override_and_source_date_epoch() {
+    let tmp = TempDir::new().unwrap();
+    let output_dir = tmp.path().join("dist");
+    write_two_page_site(&output_dir);
+
+    let config_path = tmp.path().join("graphgarden.toml");
+    fs::write(
+        &config_path,
+        minimal_config("https://test.dev/", output_dir.to_str().unwrap()),
+    )
+    .unwrap();
+    let json_path = output_dir.join(".well-known/graphgarden.json");
+
+    cargo_bin_cmd!("graphgarden")
+        .args(["build", "--config", config_path.to_str().unwrap()])
+        .env("SOURCE_DATE_EPOCH", "1771329600")
+        .assert()
+        .success();
+    let from_epoch = fs::read_to_string(&json_path).unwrap();
+    assert!(from_epoch.contains("\"generated_at\": \"2026-02-17T12:00:00Z\""));
+
+    cargo_bin_cmd!("graphgarden")
+        .args([
+            "build",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--timestamp",
+            "2026-02-17T12:00:00Z",
+        ])
+        .env("SOURCE_DATE_EPOCH", "0")
+        .assert()
+        .success();
+    let from_flag = fs::read_to_string(&json_path).unwrap();
+    assert_eq!(from_epoch, from_flag, "output should be byte-identical");
+}
+
+#[test]
+fn build_skip_unchanged_keeps_existing_file() {
+    let tmp = TempDir::new().unwrap();
+    let output_dir = tmp.path().join("dist");
+    write_two_page_site(&output_dir);
+
+    let config_path = tmp.path().join("graphgarden.toml");
+    fs::write(
+        &config_path,
+        minimal_config("https://test.dev/", output_dir.to_str().unwrap()),
+    )
+    .unwrap();
+    let json_path = output_dir.join(".well-known/graphgarden.json");
+    let build = |timestamp: &str| {
+        cargo_bin_cmd!("graphgarden")
+            .args([
+                "build",
+                "--config",
+                config_path.to_str().unwrap(),
+                "--timestamp",
+                timestamp,
+                "--skip-unchanged",
+            ])
+            .assert()
+            .success()
+    };
+
+    build("2026-02-17T12:00:00Z");
+    let first = fs::read_to_string(&json_path).unwrap();
+
+    build("2026-03-01T08:30:00Z").stdout(predicates::str::contains("is up to date"));
+    assert_eq!(fs::read_to_string(&json_path).unwrap(), first);
+
+    write_file(
+        &output_dir,
+        "contact.html",
+        "<html><head><title>Contact</title></head></html>",
+    );
+    build("2026-03-01T08:30:00Z").stdout(predicates::str::contains("wrote"));
+    let rewritten = fs::read_to_string(&json_path).unwrap();
+    assert!(rewritten.contains("2026-03-01T08:30:00Z"));
+    assert!(rewritten.contains("/contact"));
+}
+
+#[test]
+fn build_skip_unchanged_reconciles_compressed_siblings() {
+    let tmp = TempDir::new().unwrap();
+    let output_dir = tmp.path().join("dist");
+    write_two_page_site(&output_dir);
+
+    let config_path = tmp.path().join("graphgarden.toml");
+    let config = minimal_config("https://test.dev/", output_dir.to_str().unwrap());
+    let gzip_path = output_dir.join(".well-known/graphgarden.json.gz");
+    let build = || {
+        cargo_bin_cmd!("graphgarden")
+            .args([
+                "build",
+                "--config",
+                config_path.to_str().unwrap(),
+                "--skip-unchanged",
+            ])
+            .assert()
+            .success()
+    };
+
+    fs::write(&config_path, &config).unwrap();
+    build();
+    assert!(!gzip_path.exists());
+
+    fs::write(&config_path, format!("{config}gzip = 6\n")).unwrap();
+    build().stdout(predicates::str::contains("wrote"));
+    assert!(
+        gzip_path.exists(),
+        "a newly enabled sibling should be written"
+    );
+    build().stdout(predicates::str::contains("is up to date"));
+
+    fs::write(&config_path, &config).unwrap();
+    build().stdout(predicates::str::contains("wrote"));
+    assert!(!gzip_path.exists(), "a disabled sibling should be removed");
+}
+
+#[test]
+fn build_writes_compact_encoding_when_configured() {
+    let tmp = TempDir::new().unwrap();
+    let output_dir = tmp.path().join("dist");
+    write_two_page_site(&output_dir);
+
+    let config_path = tmp.path().join("graphgarden.toml");
+    let config = minimal_config("https://test.dev/", output_dir.to_str().unwrap());
+    fs::write(&config_path, format!("{config}encoding = \"compact\"\n")).unwrap();
+
+    let value = run_build_and_read_output(&config_path, &output_dir);
+    assert_eq!(value["encoding"], "compact");
+    assert!(value["edges"][0]["source"].is_u64());
+
+    let json_path = output_dir.join(".well-known/graphgarden.json");
+    cargo_bin_cmd!("graphgarden")
+        .args(["validate", json_path.to_str().unwrap()])
+        .assert()
+        .success();
+}
+
+#[test]
+fn build_skip_unchanged_rewrites_when_the_encoding_changes() {
+    let tmp = TempDir::new().unwrap();
+    let output_dir = tmp.path().join("dist");
+    write_two_page_site(&output_dir);
+
+    let config_path = tmp.path().join("graphgarden.toml");
+    let config = minimal_config("https://test.dev/", output_dir.to_str().unwrap());
+    let json_path = output_dir.join(".well-known/graphgarden.json");
+    let gzip_path = output_dir.join(".well-known/graphgarden.json.gz");
+    let build = || {
+        cargo_bin_cmd!("graphgarden")
+            .args([
+                "build",
+                "--config",
+                config_path.to_str().unwrap(),
+                "--skip-unchanged",
+            ])
+            .assert()
+            .success()
+    };
+
+    fs::write(&config_path, &config).unwrap();
+    build();
+
+    fs::write(&config_path, format!("{config}encoding = \"compact\"\n")).unwrap();
+    build().stdout(predicates::str::contains("wrote"));
+    let value: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+    assert_eq!(value["encoding"], "compact");
+    build().stdout(predicates::str::contains("is up to date"));
+
+    fs::write(&config_path, format!("{config}gzip = 1\n")).unwrap();
+    build().stdout(predicates::str::contains("wrote"));
+    let value: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+    assert!(value.get("encoding").is_none());
+    let fast = fs::read(&gzip_path).unwrap();
+
+    fs::write(&config_path, format!("{config}gzip = 9\n")).unwrap();
+    build().stdout(predicates::str::contains("wrote"));
+    assert_ne!(fs::read(&gzip_path).unwrap(), fast);
+}
+
+#[test]
+fn build_writes_shards_when_configured() {
+    let tmp = TempDir::new().unwrap();
+    let output_dir = tmp.path().join("dist");
+    write_two_page_site(&output_dir);
+
+    let config_path = tmp.path().join("graphgarden.toml");
+    let config = minimal_config("https://test.dev/", output_dir.to_str().unwrap());
+    fs::write(&config_path, format!("{config}shard = 1\n")).unwrap();
+
+    let value = run_build_and_read_output(&config_path, &output_dir);
+    assert_eq!(value["nodes"], serde_json::json!([]));
+    assert_eq!(value["shards"][0]["path"], "graphgarden/0.json");
+    assert_eq!(value["shards"][1]["nodes"], 1);
+    assert!(output_dir.join(".well-known/graphgarden/1.json").exists());
+
+    let json_path = output_dir.join(".well-known/graphgarden.json");
+    cargo_bin_cmd!("graphgarden")
+        .args(["validate", json_path.to_str().unwrap()])
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("graphgarden")
+        .args([
+            "build",
+            "--skip-unchanged",
+            "--config",
+            config_path.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("is up to date"));
+}
+
+#[test]
+fn build_signs_and_validate_verifies() {
+    let tmp = TempDir::new().unwrap();
+    let output_dir = tmp.path().join("dist");
+    write_two_page_site(&output_dir);
+    let key_path = tmp.path().join("graphgarden.key");
+
+    let keygen = cargo_bin_cmd!("graphgarden")
+        .args(["keygen", key_path.to_str().unwrap()])
+        .assert()
+        .success();
+    let stdout = String::from_utf8(keygen.get_output().stdout.clone()).unwrap();
+    let public_key = stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("public key: "))
+        .unwrap()
+        .to_owned();
+
+    let config_path = tmp.path().join("graphgarden.toml");
+    fs::write(
+        &config_path,
+        minimal_config("https://test.dev/", output_dir.to_str().unwrap()),
+    )
+    .unwrap();
+    cargo_bin_cmd!("graphgarden")
+        .args(["build", "--config", config_path.to_str().unwrap()])
+        .args(["--sign", key_path.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let json_path = output_dir.join(".well-known/graphgarden.json");
+    let value: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+    assert_eq!(value["site"]["public_key"], public_key.as_str());
+    assert!(output_dir.join(".well-known/graphgarden.json.sig").exists());
+
+    cargo_bin_cmd!("graphgarden")
+        .args([
+            "validate",
+            "--key",
+            &public_key,
+            json_path.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("is valid and signed"));
+
+    let tampered = fs::read_to_string(&json_path)
+        .unwrap()
+        .replace("\"About\"", "\"Not About\"");
+    fs::write(&json_path, tampered).unwrap();
+    cargo_bin_cmd!("graphgarden")
+        .args(["validate", "--verify", json_path.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("invalid signature"));
+}
+
+#[test]
+fn explain_prints_the_expanded_preset() {
+    let tmp = TempDir::new().unwrap();
+    let config_path = tmp.path().join("graphgarden.toml");
+    let config = minimal_config("https://test.dev/", "dist");
+    fs::write(
+        &config_path,
+        format!("{config}\n[parse]\npreset = \"mdbook\"\nexclude = [\"drafts/**\"]\n"),
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("graphgarden")
+        .args(["explain", "--config", config_path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("with the mdbook preset (mdBook)"))
+        .stdout(predicates::str::contains(
+            "exclude = [\"404.html\", \"print.html\", \"toc.html\", \"drafts/**\"]",
+        ))
+        .stdout(predicates::str::contains("page_extensions = [\".html\"]"));
+}
+
+#[test]
+fn init_writes_config_for_detected_project() {
+    let tmp = TempDir::new().unwrap();
+    write_file(tmp.path(), "hugo.toml", "baseURL = 'https://test.dev/'\n");
+    let config_path = tmp.path().join("graphgarden.toml");
+
+    cargo_bin_cmd!("graphgarden")
+        .args(["init", "--config", config_path.to_str().unwrap()])
+        .args(["--base-url", "https://test.dev/", "--title", "Test Site"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("detected a Hugo site"));
+
+    let config = fs::read_to_string(&config_path).unwrap();
+    assert!(config.contains("base_url = \"https://test.dev/\""));
+    assert!(config.contains("title    = \"Test Site\""));
+    let output_dir = tmp.path().join("public");
+    assert!(config.contains(&format!("dir = {:?}", output_dir.to_str().unwrap())));
+
+    // The generated config builds once the site is
+    write_two_page_site(&output_dir);
+    let json = run_build_and_read_output(&config_path, &output_dir);
+    assert_eq!(json["site"]["title"], "Test Site");
+}
+
+#[test]
+fn init_in_a_subdirectory_writes_an_output_dir_build_finds() {
+    let tmp = TempDir::new().unwrap();
+    let project_dir = tmp.path().join("site");
+    write_file(&project_dir, "hugo.toml", "baseURL = 'https://test.dev/'\n");
+    write_two_page_site(&project_dir.join("public"));
+
+    cargo_bin_cmd!("graphgarden")
+        .current_dir(tmp.path())
+        .args(["init", "--config", "site/graphgarden.toml"])
+        .args(["--base-url", "https://test.dev/"])
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("graphgarden")
+        .current_dir(tmp.path())
+        .args(["build", "--config", "site/graphgarden.toml"])
+        .assert()
+        .success();
+
+    let json_path = project_dir.join("public/.well-known/graphgarden.json");
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(json_path).unwrap()).unwrap();
+    assert_eq!(json["nodes"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn init_refuses_to_overwrite_without_force() {
+    let tmp = TempDir::new().unwrap();
+    let config_path = tmp.path().join("graphgarden.toml");
+    fs::write(&config_path, "# mine\n").unwrap();
+
+    cargo_bin_cmd!("graphgarden")
+        .args(["init", "--config", config_path.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("--force"));
+    assert_eq!(fs::read_to_string(&config_path).unwrap(), "# mine\n");
+
+    cargo_bin_cmd!("graphgarden")
+        .args(["init", "--force", "--config", config_path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("no known site generator"));
+    let config = fs::read_to_string(&config_path).unwrap();
+    assert!(config.contains("base_url = \"https://example.com/\""));
+    let output_dir = tmp.path().join("dist");
+    assert!(config.contains(&format!("dir = {:?}", output_dir.to_str().unwrap())));
+}
+
+#[test]
+fn init_rejects_invalid_base_url() {
+    let tmp = TempDir::new().unwrap();
+    let config_path = tmp.path().join("graphgarden.toml");
+
+    cargo_bin_cmd!("graphgarden")
+        .args(["init", "--config", config_path.to_str().unwrap()])
+        .args(["--base-url", "https://test.dev"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("invalid --base-url"));
+    assert!(!config_path.exists());
+}
+
+#[test]
+fn keygen_refuses_to_overwrite_a_key() {
+    let tmp = TempDir::new().unwrap();
+    let key_path = tmp.path().join("graphgarden.key");
+    fs::write(&key_path, "existing").unwrap();
+
+    cargo_bin_cmd!("graphgarden")
+        .args(["keygen", key_path.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("already exists"));
+    assert_eq!(fs::read_to_string(&key_path).unwrap(), "existing");
+}
+
+#[cfg(unix)]
+#[test]
+fn keygen_writes_a_key_only_the_owner_can_read() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = TempDir::new().unwrap();
+    let key_path = tmp.path().join("graphgarden.key");
+
+    cargo_bin_cmd!("graphgarden")
+        .args(["keygen", key_path.to_str().unwrap()])
+        .assert()
+        .success();
+    let mode = fs::metadata(&key_path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+}
+
+#[test]
+fn build_records_changes_between_builds() {
+    let tmp = TempDir::new().unwrap();
+    let output_dir = tmp.path().join("dist");
+    write_two_page_site(&output_dir);
+
+    let config_path = tmp.path().join("graphgarden.toml");
+    let config = minimal_config("https://test.dev/", output_dir.to_str().unwrap());
+    fs::write(&config_path, format!("{config}changes = 2\n")).unwrap();
+    let feed_path = output_dir.join(".well-known/graphgarden-changes.json");
+
+    run_build_and_read_output(&config_path, &output_dir);
+    assert!(
+        !feed_path.exists(),
+        "the first build has nothing to compare to"
+    );
+
+    write_file(
+        &output_dir,
+        "about.html",
+        "<html><head><title>About me</title></head><body><a href=\"/\">Home</a></body></html>",
+    );
+    write_file(
+        &output_dir,
+        "new.html",
+        "<html><head><title>New</title></head><body></body></html>",
+    );
+    run_build_and_read_output(&config_path, &output_dir);
+
+    let feed: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&feed_path).unwrap()).unwrap();
+    let generation = &feed["generations"][0];
+    assert_eq!(generation["added_nodes"][0]["url"], "/new");
+    assert_eq!(
+        generation["renamed_nodes"][0],
+        serde_json::json!({ "url": "/about", "from": "About", "to": "About me" })
+    );
+
+    run_build_and_read_output(&config_path, &output_dir);
+    let feed: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&feed_path).unwrap()).unwrap();
+    assert_eq!(
+        feed["generations"].as_array().unwrap().len(),
+        1,
+        "builds without changes add no generation"
+    );
+}
+
+#[test]
+fn build_warns_about_a_corrupt_change_feed() {
+    let tmp = TempDir::new().unwrap();
+    let output_dir = tmp.path().join("dist");
+    write_two_page_site(&output_dir);
+
+    let config_path = tmp.path().join("graphgarden.toml");
+    let config = minimal_config("https://test.dev/", output_dir.to_str().unwrap());
+    fs::write(&config_path, format!("{config}changes = 2\n")).unwrap();
+    run_build_and_read_output(&config_path, &output_dir);
+
+    let feed_path = output_dir.join(".well-known/graphgarden-changes.json");
+    fs::write(&feed_path, "not json").unwrap();
+    write_file(
+        &output_dir,
+        "new.html",
+        "<html><head><title>New</title></head><body></body></html>",
+    );
+
+    cargo_bin_cmd!("graphgarden")
+        .args(["build", "--config", config_path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("starting a new change feed"));
+    let feed: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&feed_path).unwrap()).unwrap();
+    assert_eq!(feed["generations"].as_array().unwrap().len(), 1);
+}
+
+const RING_MANIFEST: &str = r#"name = "Garden Ring"
+url = "https://ring.garden/"
+moderators = ["https://alice.dev/"]
+
+[[members]]
+url = "https://alice.dev/"
+name = "Alice"
+
+[[members]]
+url = "https://test.dev/"
+
+[[members]]
+url = "https://carol.dev/"
+"#;
+
+#[test]
+fn build_declares_ring_membership() {
+    let tmp = TempDir::new().unwrap();
+    let output_dir = tmp.path().join("dist");
+    write_two_page_site(&output_dir);
+    let manifest = tmp.path().join("ring.toml");
+    fs::write(&manifest, RING_MANIFEST).unwrap();
+
+    let config_path = tmp.path().join("graphgarden.toml");
+    let config = minimal_config("https://test.dev/", output_dir.to_str().unwrap());
+    fs::write(
+        &config_path,
+        format!(
+            "{config}\n[[rings]]\nmanifest = \"{}\"\n",
+            manifest.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+
+    let json = run_build_and_read_output(&config_path, &output_dir);
+    assert_eq!(
+        json["rings"],
+        serde_json::json!([{
+            "name": "Garden Ring",
+            "url": "https://ring.garden/",
+            "prev": "https://alice.dev/",
+            "next": "https://carol.dev/",
+        }])
+    );
+}
+
+#[test]
+fn build_writes_ring_navigation() {
+    let tmp = TempDir::new().unwrap();
+    let output_dir = tmp.path().join("dist");
+    write_two_page_site(&output_dir);
+    write_file(
+        &output_dir,
+        "index.html",
+        "<html><head><title>Home</title></head><body><a href=\"/about\">About</a><footer data-graphgarden-ring></footer></body></html>",
+    );
+
+    let config_path = tmp.path().join("graphgarden.toml");
+    let config = minimal_config("https://test.dev/", output_dir.to_str().unwrap());
+    fs::write(
+        &config_path,
+        format!(
+            "friends = [\"https://alice.dev/\"]\n\n{config}\n[[rings]]\nname = \"Garden Ring\"\nnav = true\nmembers = [{{ url = \"https://alice.dev/\", name = \"Alice\" }}, {{ url = \"https://test.dev/\" }}]\n"
+        ),
+    )
+    .unwrap();
+
+    let json = run_build_and_read_output(&config_path, &output_dir);
+
+    let ring_dir = output_dir.join(".well-known/graphgarden-rings/garden-ring");
+    for file in ["nav.html", "nav.json", "random.html"] {
+        assert!(ring_dir.join(file).is_file(), "{file} should be written");
+    }
+    let page = fs::read_to_string(output_dir.join("index.html")).unwrap();
+    assert!(page.contains("<footer data-graphgarden-ring><nav class=\"graphgarden-ring\""));
+
+    let urls: HashSet<&str> = json["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|node| node["url"].as_str().unwrap())
+        .collect();
+    assert_eq!(urls, HashSet::from(["/", "/about"]));
+    assert!(
+        json["edges"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|edge| edge["source"] == "/" && edge["target"] == "https://alice.dev/"),
+        "the filled placeholder links to the neighbors"
+    );
+}
+
+#[test]
+fn ring_validate_reports_violations() {
+    let tmp = TempDir::new().unwrap();
+    let manifest = tmp.path().join("ring.toml");
+    fs::write(&manifest, RING_MANIFEST).unwrap();
+
+    cargo_bin_cmd!("graphgarden")
+        .args(["ring", "validate", manifest.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("3 members"));
+
+    fs::write(
+        &manifest,
+        format!("{RING_MANIFEST}\n[[members]]\nurl = \"https://carol.dev/\"\n"),
+    )
+    .unwrap();
+    cargo_bin_cmd!("graphgarden")
+        .args(["ring", "validate", manifest.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "member `https://carol.dev/` appears more than once",
+        ));
+}
+
+#[test]
+fn ring_friends_prints_the_other_members() {
+    let tmp = TempDir::new().unwrap();
+    let manifest = tmp.path().join("ring.toml");
+    fs::write(&manifest, RING_MANIFEST).unwrap();
+
+    cargo_bin_cmd!("graphgarden")
+        .args(["ring", "friends", manifest.to_str().unwrap()])
+        .args(["--member", "https://test.dev/"])
+        .assert()
+        .success()
+        .stdout(
+            "friends = [\n  \"https://carol.dev/\",\n  { name = \"Alice\", url = \"https://alice.dev/\" },\n]\n",
+        );
+
+    cargo_bin_cmd!("graphgarden")
+        .args(["ring", "friends", manifest.to_str().unwrap()])
+        .args(["--member", "https://mallory.dev/"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("not a member of Garden Ring"));
+}
+
+#[test]
+fn build_writes_precompressed_siblings() {
+    let tmp = TempDir::new().unwrap();
+    let output_dir = tmp.path().join("dist");
+    write_two_page_site(&output_dir);
+
+    let config_path = tmp.path().join("graphgarden.toml");
+    let config = minimal_config("https://test.dev/", output_dir.to_str().unwrap());
+    fs::write(&config_path, format!("{config}gzip = 9\nbrotli = 11\n")).unwrap();
+
+    run_build_and_read_output(&config_path, &output_dir);
+
+    let well_known = output_dir.join(".well-known");
+    assert!(well_known.join("graphgarden.json.gz").exists());
+    assert!(well_known.join("graphgarden.json.br").exists());
+}
+
+#[test]
+fn build_rejects_out_of_range_compression_level() {
+    let tmp = TempDir::new().unwrap();
+    let output_dir = tmp.path().join("dist");
+    write_two_page_site(&output_dir);
+
+    let config_path = tmp.path().join("graphgarden.toml");
+    let config = minimal_config("https://test.dev/", output_dir.to_str().unwrap());
+    fs::write(&config_path, format!("{config}gzip = 10\n")).unwrap();
+
+    cargo_bin_cmd!("graphgarden")
+        .args(["build", "--config", config_path.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(
+            "invalid gzip compression level 10",
+        ));
+}
+
+#[test]
+fn build_rejects_skip_unchanged_with_watch() {
+    cargo_bin_cmd!("graphgarden")
+        .args(["build", "--watch", "--skip-unchanged"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("cannot be used with"));
+}
+
+#[test]
+fn build_watch_rebuilds_changed_pages() {
+    let tmp = TempDir::new().unwrap();
+    let output_dir = tmp.path().join("dist");
+    write_two_page_site(&output_dir);
+
+    let config_path = tmp.path().join("graphgarden.toml");
+    fs::write(
+        &config_path,
+        minimal_config("https://test.dev/", output_dir.to_str().unwrap()),
+    )
+    .unwrap();
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin!("graphgarden"))
+        .args(["build", "--watch", "--interval", "50", "--config"])
+        .arg(&config_path)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let json_path = output_dir.join(".well-known/graphgarden.json");
+    let wait_for = |predicate: &dyn Fn(&serde_json::Value) -> bool| {
+        for _ in 0..200 {
+            if let Ok(content) = fs::read_to_string(&json_path)
+                && let Ok(json) = serde_json::from_str::<serde_json::Value>(&content)
+                && predicate(&json)
+            {
+                return true;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+        false
+    };
+    let node_count = |json: &serde_json::Value| json["nodes"].as_array().unwrap().len();
+
+    let initial = wait_for(&|json| node_count(json) == 2);
+    if initial {
+        write_file(
+            &output_dir,
+            "blog/index.html",
+            r#"<html><head><title>Blog</title></head><body>
+                <a href="/">Home</a>
+            </body></html>"#,
+        );
+    }
+    let rebuilt = initial && wait_for(&|json| node_count(json) == 3);
+
+    // Static site generators wipe the output directory while building
+    if rebuilt {
+        fs::remove_dir_all(&output_dir).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        write_two_page_site(&output_dir);
+    }
+    let recovered = rebuilt && wait_for(&|json| node_count(json) == 2);
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+    assert!(initial, "watch mode should write the initial public file");
+    assert!(rebuilt, "watch mode should pick up the new page");
+    assert!(
+        recovered,
+        "watch mode should keep rebuilding after the output directory is wiped"
+    );
+}
+
+#[test]
+fn build_watch_retries_a_failed_rebuild() {
+    let tmp = TempDir::new().unwrap();
+    let output_dir = tmp.path().join("dist");
+    write_two_page_site(&output_dir);
+    // A file where the `.well-known` directory goes makes the write fail
+    let blocker = output_dir.join(".well-known");
+    fs::write(&blocker, "").unwrap();
+
+    let config_path = tmp.path().join("graphgarden.toml");
+    fs::write(
+        &config_path,
+        minimal_config("https://test.dev/", output_dir.to_str().unwrap()),
+    )
+    .unwrap();
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin!("graphgarden"))
+        .args(["build", "--watch", "--interval", "50", "--config"])
+        .arg(&config_path)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    fs::remove_file(&blocker).unwrap();
+
+    // No page changed, so only a retry writes the public file
+    let json_path = blocker.join("graphgarden.json");
+    let written = (0..200).any(|_| {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        json_path.exists()
+    });
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+    assert!(written, "watch mode should retry a failed rebuild");
+}
+
+fn http_get(address: &str, path: &str) -> String {
+    http_request(address, "GET", path)
+}
+
+fn http_request(address: &str, method: &str, path: &str) -> String {
+    use std::io::{Read, Write};
+
+    let mut stream = std::net::TcpStream::connect(address).unwrap();
+    write!(
+        stream,
+        "{method} {path} HTTP/1.1\r\nHost: {address}\r\nConnection: close\r\n\r\n"
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+/// A running `graphgarden serve`, listening on a free port.
+struct TestServer {
+    child: std::process::Child,
+    // Kept open until the server is stopped, so that its logging does not fail
+    _stdout: std::io::BufReader<std::process::ChildStdout>,
+    address: String,
+}
+
+impl TestServer {
+    fn stop(&mut self) {
+        self.child.kill().unwrap();
+        self.child.wait().unwrap();
+    }
+}
+
+fn spawn_server(config_path: &Path, args: &[&str]) -> TestServer {
+    use std::io::BufRead;
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin!("graphgarden"))
+        .args(["serve", "--port", "0", "--config"])
+        .arg(config_path)
+        .args(args)
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut stdout = std::io::BufReader::new(child.stdout.take().unwrap());
+    let mut first_line = String::new();
+    stdout.read_line(&mut first_line).unwrap();
+    let address = first_line
+        .trim_end()
+        .rsplit("http://")
+        .next()
+        .unwrap()
+        .trim_end_matches('/')
+        .to_owned();
+
+    TestServer {
+        child,
+        _stdout: stdout,
+        address,
+    }
+}
+
+#[test]
+fn serve_sends_protocol_headers_and_mounts_friends() {
+    let tmp = TempDir::new().unwrap();
+    let output_dir = tmp.path().join("dist");
+    write_two_page_site(&output_dir);
+    let bob_dir = tmp.path().join("bob");
+    write_file(
+        &bob_dir,
+        ".well-known/graphgarden.json",
+        r#"{"version":"0.1.0"}"#,
+    );
+
+    let config_path = tmp.path().join("graphgarden.toml");
+    let config = minimal_config("https://test.dev/", output_dir.to_str().unwrap());
+    fs::write(
+        &config_path,
+        format!("friends = [\"https://bob.dev/\"]\n\n{config}"),
+    )
+    .unwrap();
+    run_build_and_read_output(&config_path, &output_dir);
+
+    let mut server = spawn_server(
+        &config_path,
+        &[
+            "--friend",
+            &format!("https://bob.dev/={}", bob_dir.display()),
+        ],
+    );
+    let address = server.address.clone();
+
+    let own = http_get(&address, "/.well-known/graphgarden.json");
+    let friend = http_get(&address, "/_friends/bob.dev/.well-known/graphgarden.json");
+    let page = http_get(&address, "/about");
+
+    server.stop();
+
+    assert!(own.starts_with("HTTP/1.1 200"), "{own}");
+    assert!(own.contains("Access-Control-Allow-Origin: *"));
+    assert!(own.contains("Cache-Control: public, max-age=3600"));
+    assert!(own.contains("ETag: \""));
+    assert!(own.contains(&format!(
+        "\"public_file\":\"http://{address}/_friends/bob.dev/.well-known/graphgarden.json\""
+    )));
+
+    assert!(friend.starts_with("HTTP/1.1 200"), "{friend}");
+    assert!(friend.ends_with(r#"{"version":"0.1.0"}"#));
+
+    assert!(page.starts_with("HTTP/1.1 200"), "{page}");
+    assert!(page.contains("<title>About</title>"));
+    assert!(!page.contains("Access-Control-Allow-Origin"));
+}
+
+#[test]
+fn serve_answers_head_with_the_length_and_no_body() {
+    let tmp = TempDir::new().unwrap();
+    let output_dir = tmp.path().join("dist");
+    write_two_page_site(&output_dir);
+    // Large enough for the server to stream a GET in chunks
+    let page = format!("<html><body>{}</body></html>", "x".repeat(40_000));
+    write_file(&output_dir, "large.html", &page);
+
+    let config_path = tmp.path().join("graphgarden.toml");
+    fs::write(
+        &config_path,
+        minimal_config("https://test.dev/", output_dir.to_str().unwrap()),
+    )
+    .unwrap();
+
+    let mut server = spawn_server(&config_path, &[]);
+    let head = http_request(&server.address, "HEAD", "/large");
+    server.stop();
+
+    assert!(head.starts_with("HTTP/1.1 200"), "{head}");
+    assert!(
+        head.contains(&format!("Content-Length: {}", page.len())),
+        "{head}"
+    );
+    assert!(head.ends_with("\r\n\r\n"), "{head}");
+}
+
+#[test]
+fn merge_stitches_local_friends_and_skips_unreachable_ones() {
+    let tmp = TempDir::new().unwrap();
+
+    let bob_dir = tmp.path().join("bob");
+    write_file(
+        &bob_dir,
+        "index.html",
+        r#"<html><head><title>Bob</title></head><body><a href="https://alice.dev/about">Alice</a></body></html>"#,
+    );
+    let bob_config = tmp.path().join("bob.toml");
+    fs::write(
+        &bob_config,
+        format!(
+            "friends = [\"https://alice.dev/\"]\n\n{}",
+            minimal_config("https://bob.dev/", bob_dir.to_str().unwrap())
+        ),
+    )
+    .unwrap();
+    run_build_and_read_output(&bob_config, &bob_dir);
+
+    let alice_dir = tmp.path().join("alice");
+    write_two_page_site(&alice_dir);
+    let alice_config = tmp.path().join("alice.toml");
+    fs::write(
+        &alice_config,
+        format!(
+            "friends = [\"https://bob.dev/\", \"http://127.0.0.1:9/\"]\n\n{}",
+            minimal_config("https://alice.dev/", alice_dir.to_str().unwrap())
+        ),
+    )
+    .unwrap();
+    run_build_and_read_output(&alice_config, &alice_dir);
+
+    cargo_bin_cmd!("graphgarden")
+        .args(["merge", "--config", alice_config.to_str().unwrap()])
+        .arg("--friend")
+        .arg(format!("https://bob.dev/={}", bob_dir.display()))
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("skipping http://127.0.0.1:9/"));
+
+    let content = fs::read_to_string(alice_dir.join("graphgarden-merged.json")).unwrap();
+    let merged: serde_json::Value = serde_json::from_str(&content).unwrap();
+    assert_eq!(merged["base_url"], "https://alice.dev/");
+
+    let sites: Vec<_> = merged["sites"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|site| site["base_url"].as_str().unwrap())
+        .collect();
+    assert_eq!(sites, ["https://alice.dev/", "https://bob.dev/"]);
+
+    let nodes: HashSet<_> = merged["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|node| node["url"].as_str().unwrap())
+        .collect();
+    assert!(nodes.contains("https://alice.dev/"));
+    assert!(nodes.contains("https://bob.dev/"));
+
+    let bob_edge = merged["edges"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|edge| edge["source"] == "https://bob.dev/")
+        .unwrap();
+    assert_eq!(bob_edge["target"], "https://alice.dev/about");
+}
+
+#[test]
+fn merge_caches_friend_files_and_falls_back_when_they_are_down() {
+    let tmp = TempDir::new().unwrap();
+
+    let bob_dir = tmp.path().join("bob");
+    write_file(
+        &bob_dir,
+        "index.html",
+        "<html><head><title>Bob</title></head><body></body></html>",
+    );
+    let bob_config = tmp.path().join("bob.toml");
+    fs::write(
+        &bob_config,
+        minimal_config("https://bob.dev/", bob_dir.to_str().unwrap()),
+    )
+    .unwrap();
+    run_build_and_read_output(&bob_config, &bob_dir);
+    let mut bob = spawn_server(&bob_config, &["--max-age", "0"]);
+
+    let alice_dir = tmp.path().join("alice");
+    write_two_page_site(&alice_dir);
+    let cache_dir = tmp.path().join("cache");
+    let alice_config = tmp.path().join("alice.toml");
+    fs::write(
+        &alice_config,
+        format!(
+            "friends = [\"http://{}/\"]\n\n{}\n[cache]\ndir = \"{}\"\n",
+            bob.address,
+            minimal_config("https://alice.dev/", alice_dir.to_str().unwrap()),
+            cache_dir.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+    run_build_and_read_output(&alice_config, &alice_dir);
+
+    let merge = || {
+        cargo_bin_cmd!("graphgarden")
+            .args(["merge", "--config", alice_config.to_str().unwrap()])
+            .assert()
+            .success()
+    };
+    let merged_sites = || {
+        let content = fs::read_to_string(alice_dir.join("graphgarden-merged.json")).unwrap();
+        let merged: serde_json::Value = serde_json::from_str(&content).unwrap();
+        merged["sites"].as_array().unwrap().len()
+    };
+
+    merge();
+    assert_eq!(merged_sites(), 2);
+    assert_eq!(fs::read_dir(&cache_dir).unwrap().count(), 1);
+
+    bob.stop();
+    merge().stderr(predicates::str::contains("is unreachable"));
+    assert_eq!(merged_sites(), 2);
+
+    cargo_bin_cmd!("graphgarden")
+        .args(["cache", "prune", "--all", "--config"])
+        .arg(&alice_config)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("removed 1 file(s)"));
+
+    merge().stderr(predicates::str::contains("skipping"));
+    assert_eq!(merged_sites(), 1);
+}
+
+#[test]
+fn cache_prune_does_not_need_a_built_site() {
+    let tmp = TempDir::new().unwrap();
+    let cache_dir = tmp.path().join("cache");
+    fs::create_dir_all(&cache_dir).unwrap();
+    fs::write(cache_dir.join("entry.json"), "{}").unwrap();
+
+    let config_path = tmp.path().join("graphgarden.toml");
+    let output_dir = tmp.path().join("dist");
+    let config = minimal_config("https://test.dev/", output_dir.to_str().unwrap());
+    fs::write(
+        &config_path,
+        format!(
+            "{config}\n[cache]\ndir = {:?}\n",
+            cache_dir.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("graphgarden")
+        .args([
+            "cache",
+            "prune",
+            "--days",
+            "18446744073709551615",
+            "--config",
+        ])
+        .arg(&config_path)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("removed 1 file(s)"));
+}
@@ -4,10 +4,15 @@ import { resolve } from "node:path";
 import Graph from "graphology";
 import {
 	isGraphGardenFile,
+	expandCompactFile,
+	fetchShards,
 	buildGraph,
+	buildMergedGraph,
+	isMergedGraph,
 	fetchFriendGraphs,
 	assignLayout,
 	GraphGardenFile,
+	GraphGardenMergedGraph,
 	GraphGarden,
 	DEFAULT_CONFIG,
 } from "./index.js";
@@ -71,12 +76,30 @@ describe("isGraphGardenFile", () => {
 		expect(isGraphGardenFile(file)).toBe(false);
 	});
 
-	test("invalid edge with wrong type returns false", () => {
+	test("node with non-string group returns false", () => {
 		const file = validFile();
-		file.edges = [{ source: "/a", target: "/b", type: "unknown" }];
+		file.nodes = [{ url: "/page", title: "Page", group: 42 }];
 		expect(isGraphGardenFile(file)).toBe(false);
 	});
 
+	test("invalid edge with non-string type returns false", () => {
+		const file = validFile();
+		file.edges = [{ source: "/a", target: "/b", type: 42 }];
+		expect(isGraphGardenFile(file)).toBe(false);
+	});
+
+	test("edge with non-string context returns false", () => {
+		const file = validFile();
+		file.edges = [{ source: "/a", target: "/b", type: "internal", context: 1 }];
+		expect(isGraphGardenFile(file)).toBe(false);
+	});
+
+	test("edge with custom type is valid", () => {
+		const file = validFile();
+		file.edges = [{ source: "/a", target: "/b", type: "related" }];
+		expect(isGraphGardenFile(file)).toBe(true);
+	});
+
 	test("invalid edge missing source returns false", () => {
 		const file = validFile();
 		file.edges = [{ target: "/b", type: "friend" }];
@@ -136,6 +159,18 @@ describe("isGraphGardenFile", () => {
 		expect(isGraphGardenFile(file)).toBe(false);
 	});
 
+	test("friend objects are valid", () => {
+		const file = validFile();
+		file.friends = ["https://valid.com", { url: "https://rich.com", name: "Rich" }];
+		expect(isGraphGardenFile(file)).toBe(true);
+	});
+
+	test("friend object without url returns false", () => {
+		const file = validFile();
+		file.friends = [{ name: "Nameless" }];
+		expect(isGraphGardenFile(file)).toBe(false);
+	});
+
 	test("empty friends array returns true", () => {
 		const file = validFile();
 		file.friends = [];
@@ -143,6 +178,118 @@ describe("isGraphGardenFile", () => {
 	});
 });
 
+describe("expandCompactFile", () => {
+	test("resolves edge indexes into nodes and targets", () => {
+		const compact = {
+			...validFile(),
+			encoding: "compact",
+			nodes: [
+				{ url: "/", title: "Home" },
+				{ url: "/about", title: "About" },
+			],
+			targets: ["https://friend.com/"],
+			edges: [
+				{ source: 0, target: 1, type: "internal" },
+				{ source: 1, target: 2, type: "friend" },
+			],
+		};
+
+		const expanded = expandCompactFile(compact);
+
+		expect(isGraphGardenFile(expanded)).toBe(true);
+		expect((expanded as GraphGardenFile).edges).toEqual([
+			{ source: "/", target: "/about", type: "internal" },
+			{ source: "/about", target: "https://friend.com/", type: "friend" },
+		]);
+		expect(expanded).not.toHaveProperty("targets");
+	});
+
+	test("returns expanded files unchanged", () => {
+		const file = validFile();
+		expect(expandCompactFile(file)).toBe(file);
+	});
+
+	test("out-of-range indexes fail validation", () => {
+		const compact = {
+			...validFile(),
+			encoding: "compact",
+			edges: [{ source: 0, target: 9, type: "internal" }],
+		};
+		expect(isGraphGardenFile(expandCompactFile(compact))).toBe(false);
+	});
+});
+
+describe("fetchShards", () => {
+	afterEach(() => {
+		vi.unstubAllGlobals();
+	});
+
+	test("merges shard nodes and edges, resolved against the index URL", async () => {
+		const index = {
+			...validFile(),
+			nodes: [],
+			edges: [],
+			shards: [
+				{ path: "graphgarden/0.json", nodes: 1, edges: 1 },
+				{ path: "graphgarden/1.json", nodes: 1, edges: 0 },
+			],
+		};
+		const shards: Record<string, unknown> = {
+			"https://example.com/.well-known/graphgarden/0.json": {
+				nodes: [{ url: "/", title: "Home" }],
+				edges: [{ source: "/", target: "/docs/", type: "internal" }],
+			},
+			"https://example.com/.well-known/graphgarden/1.json": {
+				nodes: [{ url: "/docs/", title: "Docs" }],
+				edges: [],
+			},
+		};
+		const fetchMock = vi.fn((url: string) =>
+			Promise.resolve({ ok: true, json: () => Promise.resolve(shards[url]) }),
+		);
+		vi.stubGlobal("fetch", fetchMock);
+
+		const merged = await fetchShards(index, "https://example.com/.well-known/graphgarden.json");
+
+		expect(fetchMock).toHaveBeenCalledTimes(2);
+		expect(isGraphGardenFile(merged)).toBe(true);
+		expect((merged as GraphGardenFile).nodes.map((node) => node.url)).toEqual(["/", "/docs/"]);
+		expect((merged as GraphGardenFile).edges).toHaveLength(1);
+		expect(merged).not.toHaveProperty("shards");
+	});
+
+	test("returns unsharded files unchanged", async () => {
+		const file = validFile();
+		expect(await fetchShards(file, "https://example.com/.well-known/graphgarden.json")).toBe(file);
+	});
+
+	test("rejects when a shard is unavailable", async () => {
+		vi.stubGlobal(
+			"fetch",
+			vi.fn().mockResolvedValue({ ok: false, status: 404, statusText: "Not Found" }),
+		);
+		const index = { ...validFile(), shards: [{ path: "graphgarden/0.json", nodes: 1, edges: 0 }] };
+
+		await expect(
+			fetchShards(index, "https://example.com/.well-known/graphgarden.json"),
+		).rejects.toThrow("404");
+	});
+
+	test("rejects shard paths outside the shard directory without fetching them", async () => {
+		const fetchMock = vi.fn();
+		vi.stubGlobal("fetch", fetchMock);
+		const index = {
+			...validFile(),
+			shards: [{ path: "https://evil.example/0.json", nodes: 1, edges: 0 }],
+		};
+
+		await expect(
+			fetchShards(index, "https://example.com/.well-known/graphgarden.json"),
+		).rejects.toThrow("not under graphgarden/");
+		expect(fetchMock).not.toHaveBeenCalled();
+	});
+});
+
 describe("buildGraph", () => {
 	test("correct number of nodes", () => {
 		const graph = buildGraph(validFile() as unknown as GraphGardenFile, DEFAULT_CONFIG);
@@ -155,6 +302,13 @@ describe("buildGraph", () => {
 		expect(graph.getNodeAttributes("https://example.com/page")).toHaveProperty("title", "Page");
 	});
 
+	test("node attributes contain group when present", () => {
+		const file = validFile();
+		file.nodes = [{ url: "/page", title: "Page", group: "post" }];
+		const graph = buildGraph(file as unknown as GraphGardenFile, DEFAULT_CONFIG);
+		expect(graph.getNodeAttributes("https://example.com/page")).toHaveProperty("group", "post");
+	});
+
 	test("correct number of edges", () => {
 		const graph = buildGraph(validFile() as unknown as GraphGardenFile, DEFAULT_CONFIG);
 		expect(graph.size).toBe(1);
@@ -166,6 +320,25 @@ describe("buildGraph", () => {
 		expect(graph.getEdgeAttributes(edge)).toHaveProperty("type", "friend");
 	});
 
+	test("custom edge types keep their kind and render as a registered program", () => {
+		const file = validFile();
+		file.edges = [
+			{ source: "/page", target: "/other", type: "related" },
+			{ source: "/page", target: "https://friend.com/post", type: "citation" },
+		];
+		const graph = buildGraph(file as unknown as GraphGardenFile, DEFAULT_CONFIG);
+
+		const related = graph.edge("https://example.com/page", "https://example.com/other")!;
+		expect(graph.getEdgeAttributes(related)).toMatchObject({ type: "internal", kind: "related" });
+
+		const citation = graph.edge("https://example.com/page", "https://friend.com/post")!;
+		expect(graph.getEdgeAttributes(citation)).toMatchObject({
+			type: "friend",
+			kind: "citation",
+			color: DEFAULT_CONFIG.friendEdgeColor,
+		});
+	});
+
 	test("graph attributes contain base_url and site", () => {
 		const graph = buildGraph(validFile() as unknown as GraphGardenFile, DEFAULT_CONFIG);
 		expect(graph.getAttribute("base_url")).toBe("https://example.com");
@@ -261,6 +434,68 @@ describe("buildGraph", () => {
 	});
 });
 
+function mergedGraph(): GraphGardenMergedGraph {
+	return {
+		version: "0.2.0",
+		generated_at: "2025-01-01T00:00:00Z",
+		base_url: "https://example.com/",
+		sites: [
+			{ base_url: "https://example.com/", site: { title: "Test Site" } },
+			{ base_url: "https://friend.com/", site: { title: "Friend" } },
+		],
+		nodes: [
+			{ url: "https://example.com/page", title: "Page", site: "https://example.com/" },
+			{ url: "https://friend.com/", title: "Friend Home", site: "https://friend.com/" },
+		],
+		edges: [
+			{ source: "https://example.com/page", target: "https://friend.com/", type: "friend" },
+			{ source: "https://friend.com/", target: "https://friend.com/gone", type: "internal" },
+		],
+	};
+}
+
+describe("isMergedGraph", () => {
+	test("valid merged graph returns true", () => {
+		expect(isMergedGraph(mergedGraph())).toBe(true);
+	});
+
+	test("public files are not merged graphs", () => {
+		expect(isMergedGraph(validFile())).toBe(false);
+	});
+
+	test("nodes without a site return false", () => {
+		const merged = mergedGraph() as unknown as Record<string, unknown>;
+		merged.nodes = [{ url: "https://example.com/page", title: "Page" }];
+		expect(isMergedGraph(merged)).toBe(false);
+	});
+});
+
+describe("buildMergedGraph", () => {
+	test("colors nodes by the site declaring them", () => {
+		const graph = buildMergedGraph(mergedGraph(), DEFAULT_CONFIG);
+		expect(graph.order).toBe(3);
+		expect(graph.getNodeAttribute("https://example.com/page", "color")).toBe(
+			DEFAULT_CONFIG.localNodeColor,
+		);
+		expect(graph.getNodeAttribute("https://friend.com/", "color")).toBe(
+			DEFAULT_CONFIG.friendNodeColor,
+		);
+		expect(graph.getNodeAttribute("https://friend.com/gone", "color")).toBe(
+			DEFAULT_CONFIG.frontierNodeColor,
+		);
+	});
+
+	test("edges of friends use the friend edge color", () => {
+		const graph = buildMergedGraph(mergedGraph(), DEFAULT_CONFIG);
+		const edge = graph.edge("https://friend.com/", "https://friend.com/gone")!;
+		expect(graph.getEdgeAttributes(edge)).toMatchObject({
+			type: "internal",
+			color: DEFAULT_CONFIG.friendEdgeColor,
+		});
+		expect(graph.getAttribute("site")).toEqual({ title: "Test Site" });
+	});
+});
+
 describe("buildGraph with fixture data", () => {
 	const fixturePath = resolve(import.meta.dirname, "../../../fixtures/bob/graphgarden.json");
 	const raw = readFileSync(fixturePath, "utf-8");
@@ -374,6 +609,29 @@ describe("fetchFriendGraphs", () => {
 		expect(fetch).toHaveBeenCalledWith("https://friend.test/.well-known/graphgarden.json");
 	});
 
+	test("labels unfetched friend nodes with their declared name", () => {
+		const file: GraphGardenFile = {
+			...localFileWithFriend(),
+			friends: [{ url: "https://friend.test/", name: "Friend" }],
+		};
+		const graph = buildGraph(file, DEFAULT_CONFIG);
+
+		expect(graph.getNodeAttribute("https://friend.test/", "label")).toBe("Friend");
+	});
+
+	test("fetches a friend's explicit public file location", async () => {
+		const file: GraphGardenFile = {
+			...localFileWithFriend(),
+			friends: [{ url: "https://friend.test/", public_file: "https://friend.test/graph.json" }],
+		};
+		const graph = buildGraph(file, DEFAULT_CONFIG);
+		stubFetchWith({ json: () => Promise.resolve(friendFile()) });
+
+		await fetchFriendGraphs(graph, DEFAULT_CONFIG, file.friends);
+
+		expect(fetch).toHaveBeenCalledWith("https://friend.test/graph.json");
+	});
+
 	test("handles fetch rejection gracefully", async () => {
 		const file = localFileWithFriend();
 		const graph = buildGraph(file, DEFAULT_CONFIG);
@@ -798,6 +1056,25 @@ describe("GraphGarden custom element", () => {
 		element.remove();
 	});
 
+	test("src attribute loads a merged graph without fetching friends", async () => {
+		const fetchMock = vi.fn().mockResolvedValue({
+			ok: true,
+			json: () => Promise.resolve(mergedGraph()),
+		});
+		vi.stubGlobal("fetch", fetchMock);
+
+		const element = document.createElement("graph-garden") as GraphGarden;
+		element.setAttribute("src", "/graphgarden-merged.json");
+		document.body.appendChild(element);
+		await new Promise((resolve) => setTimeout(resolve, 50));
+
+		expect(fetchMock).toHaveBeenCalledTimes(1);
+		expect(fetchMock).toHaveBeenCalledWith("/graphgarden-merged.json");
+		expect(element.graph!.hasNode("https://friend.com/")).toBe(true);
+
+		element.remove();
+	});
+
 	test("successful fetch populates graph with layout coordinates", async () => {
 		vi.stubGlobal(
 			"fetch",
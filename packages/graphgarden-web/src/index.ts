@@ -34,18 +34,37 @@ export const DEFAULT_CONFIG: GraphGardenConfig = {
 export interface GraphGardenNode {
 	url: string;
 	title: string;
+	group?: string;
 }
 
 export interface GraphGardenEdge {
 	source: string;
 	target: string;
-	type: "internal" | "friend";
+	/** `"internal"`, `"friend"`, or a custom type such as `"related"`. */
+	type: string;
+	/** Region of the source page the link lives in, e.g. `"nav"` or `"main"`. */
+	context?: string;
 }
 
 export interface GraphGardenSite {
 	title: string;
 	description?: string;
 	language?: string;
+	/** Base64 ed25519 key the file is signed with. */
+	public_key?: string;
+}
+
+/** A friend site declared with details, instead of a bare URL. */
+export interface GraphGardenFriend {
+	url: string;
+	name?: string;
+	description?: string;
+	/** e.g. XFN values such as `"friend met"`. */
+	relationship?: string;
+	/** Location of the friend's file, when not at its well-known path. */
+	public_file?: string;
+	/** Base64 ed25519 key the friend's file must be signed with. */
+	public_key?: string;
 }
 
 /** The top-level shape of a `graphgarden.json` file. */
@@ -54,9 +73,63 @@ export interface GraphGardenFile {
 	generated_at: string;
 	base_url: string;
 	site: GraphGardenSite;
-	friends?: string[];
+	friends?: (string | GraphGardenFriend)[];
 	nodes: GraphGardenNode[];
 	edges: GraphGardenEdge[];
+	/** Webrings the site belongs to. */
+	rings?: GraphGardenRingMembership[];
+	/** Shard files holding the nodes and edges, when the file is a sharded index. */
+	shards?: GraphGardenShardRef[];
+}
+
+/** A site whose graph is part of a {@link GraphGardenMergedGraph}. */
+export interface GraphGardenMergedSite {
+	base_url: string;
+	site: GraphGardenSite;
+}
+
+/** A page of a {@link GraphGardenMergedGraph}, with an absolute URL. */
+export interface GraphGardenMergedNode {
+	url: string;
+	title: string;
+	group?: string;
+	/** Base URL of the site declaring the page. */
+	site: string;
+}
+
+/**
+ * A site's graph stitched together with its friends' at build time, by
+ * `graphgarden merge`. Every URL is absolute, and no friend needs fetching.
+ */
+export interface GraphGardenMergedGraph {
+	version: string;
+	generated_at: string;
+	base_url: string;
+	/** The merged sites, the site itself first. */
+	sites: GraphGardenMergedSite[];
+	nodes: GraphGardenMergedNode[];
+	/** Targets missing from `nodes` are frontier pages. */
+	edges: GraphGardenEdge[];
+}
+
+/** A webring the site belongs to, with its neighbors in the ring. */
+export interface GraphGardenRingMembership {
+	name: string;
+	/** Home page of the ring. */
+	url?: string;
+	/** Base URL of the previous member. */
+	prev: string;
+	/** Base URL of the next member. */
+	next: string;
+}
+
+/** A shard listed in a sharded index. */
+export interface GraphGardenShardRef {
+	/** Location of the shard file, relative to the index file URL. */
+	path: string;
+	prefix?: string;
+	nodes: number;
+	edges: number;
 }
 
 function isNode(v: unknown): v is GraphGardenNode {
@@ -64,19 +137,111 @@ function isNode(v: unknown): v is GraphGardenNode {
 		typeof v === "object" &&
 		v !== null &&
 		typeof (v as Record<string, unknown>).url === "string" &&
-		typeof (v as Record<string, unknown>).title === "string"
+		typeof (v as Record<string, unknown>).title === "string" &&
+		((v as Record<string, unknown>).group === undefined ||
+			typeof (v as Record<string, unknown>).group === "string")
+	);
+}
+
+function isFriend(v: unknown): v is string | GraphGardenFriend {
+	if (typeof v === "string") return true;
+	if (typeof v !== "object" || v === null) return false;
+	const friend = v as Record<string, unknown>;
+	return (
+		typeof friend.url === "string" &&
+		["name", "description", "relationship", "public_file", "public_key"].every(
+			(key) => friend[key] === undefined || typeof friend[key] === "string",
+		)
 	);
 }
 
+function friendUrl(friend: string | GraphGardenFriend): string {
+	return typeof friend === "string" ? friend : friend.url;
+}
+
 function isEdge(v: unknown): v is GraphGardenEdge {
 	return (
 		typeof v === "object" &&
 		v !== null &&
 		typeof (v as Record<string, unknown>).source === "string" &&
 		typeof (v as Record<string, unknown>).target === "string" &&
-		((v as Record<string, unknown>).type === "internal" ||
-			(v as Record<string, unknown>).type === "friend")
+		typeof (v as Record<string, unknown>).type === "string" &&
+		(v as Record<string, unknown>).type !== "" &&
+		((v as Record<string, unknown>).context === undefined ||
+			typeof (v as Record<string, unknown>).context === "string")
+	);
+}
+
+/**
+ * Sigma program used to draw an edge. Custom types are drawn like the
+ * built-in type matching their target: absolute URLs point to friends.
+ */
+function edgeProgramType(edge: GraphGardenEdge): "internal" | "friend" {
+	if (edge.type === "internal" || edge.type === "friend") return edge.type;
+	return /^https?:\/\//.test(edge.target) ? "friend" : "internal";
+}
+
+/**
+ * Expand a file in the compact encoding, where edge `source`/`target` are
+ * indexes into `nodes` followed by `targets`. Other values are returned as-is,
+ * so the result can go straight to {@link isGraphGardenFile}.
+ */
+export function expandCompactFile(value: unknown): unknown {
+	if (typeof value !== "object" || value === null) return value;
+	const { encoding, targets = [], ...file } = value as Record<string, unknown>;
+	if (encoding !== "compact") return value;
+	if (!Array.isArray(file.nodes) || !Array.isArray(file.edges) || !Array.isArray(targets)) {
+		return value;
+	}
+
+	const urls: unknown[] = [
+		...file.nodes.map((node) => (node as Record<string, unknown> | null)?.url),
+		...targets,
+	];
+	const urlAt = (index: unknown) => (typeof index === "number" ? urls[index] : undefined);
+	return {
+		...file,
+		edges: file.edges.map((edge) => {
+			if (typeof edge !== "object" || edge === null) return edge;
+			const compact = edge as Record<string, unknown>;
+			return { ...compact, source: urlAt(compact.source), target: urlAt(compact.target) };
+		}),
+	};
+}
+
+/** Shard paths must stay in the shard directory: relative, under `graphgarden/`, with plain segments. */
+const SHARD_PATH = /^graphgarden(\/(?!\.\.?(?:\/|$))[A-Za-z0-9._-]+)+$/;
+
+/**
+ * Fetch the shards of a sharded index, resolved against `indexUrl`, and merge
+ * their nodes and edges into it. Other values are returned as-is, so the
+ * result can go straight to {@link isGraphGardenFile}.
+ */
+export async function fetchShards(value: unknown, indexUrl: string): Promise<unknown> {
+	if (typeof value !== "object" || value === null) return value;
+	const { shards, ...file } = value as Record<string, unknown>;
+	if (!Array.isArray(shards) || shards.length === 0) return value;
+	if (!Array.isArray(file.nodes) || !Array.isArray(file.edges)) return value;
+
+	const parts = await Promise.all(
+		shards.map(async (shard: GraphGardenShardRef) => {
+			if (typeof shard.path !== "string" || !SHARD_PATH.test(shard.path)) {
+				throw new Error(`shard path ${String(shard.path)} is not under graphgarden/`);
+			}
+			const location = new URL(shard.path, new URL(indexUrl, document.baseURI)).href;
+			const response = await fetch(location);
+			if (!response.ok) {
+				throw new Error(`shard ${location} responded ${response.status} ${response.statusText}`);
+			}
+			return (await response.json()) as Record<string, unknown>;
+		}),
 	);
+
+	return {
+		...file,
+		nodes: [...file.nodes, ...parts.flatMap((part) => (Array.isArray(part.nodes) ? part.nodes : []))],
+		edges: [...file.edges, ...parts.flatMap((part) => (Array.isArray(part.edges) ? part.edges : []))],
+	};
 }
 
 /** Runtime check that `value` matches the {@link GraphGardenFile} shape. */
@@ -96,7 +261,7 @@ export function isGraphGardenFile(value: unknown): value is GraphGardenFile {
 
 	if (
 		obj.friends !== undefined &&
-		(!Array.isArray(obj.friends) || !obj.friends.every((f: unknown) => typeof f === "string"))
+		(!Array.isArray(obj.friends) || !obj.friends.every(isFriend))
 	)
 		return false;
 
@@ -106,6 +271,78 @@ export function isGraphGardenFile(value: unknown): value is GraphGardenFile {
 	return true;
 }
 
+/** Runtime check that `value` matches the {@link GraphGardenMergedGraph} shape. */
+export function isMergedGraph(value: unknown): value is GraphGardenMergedGraph {
+	if (typeof value !== "object" || value === null) return false;
+	const obj = value as Record<string, unknown>;
+
+	if (typeof obj.version !== "string") return false;
+	if (typeof obj.generated_at !== "string") return false;
+	if (typeof obj.base_url !== "string") return false;
+
+	const isSite = (v: unknown) =>
+		typeof v === "object" &&
+		v !== null &&
+		typeof (v as Record<string, unknown>).base_url === "string" &&
+		typeof (v as Record<string, unknown>).site === "object" &&
+		(v as Record<string, unknown>).site !== null &&
+		typeof ((v as Record<string, unknown>).site as Record<string, unknown>).title === "string";
+	if (!Array.isArray(obj.sites) || !obj.sites.every(isSite)) return false;
+
+	const isMergedNode = (v: unknown) =>
+		isNode(v) && typeof (v as unknown as Record<string, unknown>).site === "string";
+	if (!Array.isArray(obj.nodes) || !obj.nodes.every(isMergedNode)) return false;
+	if (!Array.isArray(obj.edges) || !obj.edges.every(isEdge)) return false;
+
+	return true;
+}
+
+/**
+ * Build a graphology `Graph` from a {@link GraphGardenMergedGraph}, colored
+ * as if the friends had been fetched by {@link fetchFriendGraphs}.
+ */
+export function buildMergedGraph(merged: GraphGardenMergedGraph, config: GraphGardenConfig): Graph {
+	const graph = new Graph();
+
+	graph.replaceAttributes({
+		base_url: merged.base_url,
+		site: merged.sites.find((site) => site.base_url === merged.base_url)?.site,
+	});
+
+	const siteOf = new Map<string, string>();
+	for (const node of merged.nodes) {
+		const local = node.site === merged.base_url;
+		siteOf.set(node.url, node.site);
+		graph.mergeNode(node.url, {
+			title: node.title,
+			label: node.title,
+			size: config.nodeSize,
+			color: local ? config.localNodeColor : config.friendNodeColor,
+			...(node.group !== undefined && { group: node.group }),
+		});
+	}
+
+	for (const edge of merged.edges) {
+		graph.mergeNode(edge.source, { size: config.nodeSize });
+		graph.mergeNode(edge.target, {
+			size: config.nodeSize,
+			...(!siteOf.has(edge.target) && { color: config.frontierNodeColor }),
+		});
+
+		const programType = edgeProgramType(edge);
+		const local = siteOf.get(edge.source) === merged.base_url;
+		graph.mergeDirectedEdge(edge.source, edge.target, {
+			type: programType,
+			kind: edge.type,
+			color: local && programType === "internal" ? config.localEdgeColor : config.friendEdgeColor,
+			size: config.edgeSize,
+			...(edge.context !== undefined && { context: edge.context }),
+		});
+	}
+
+	return graph;
+}
+
 /** Build a graphology `Graph` from a parsed {@link GraphGardenFile}. */
 export function buildGraph(file: GraphGardenFile, config: GraphGardenConfig): Graph {
 	const graph = new Graph();
@@ -124,6 +361,7 @@ export function buildGraph(file: GraphGardenFile, config: GraphGardenConfig): Gr
 			label: node.title,
 			size: config.nodeSize,
 			color: config.localNodeColor,
+			...(node.group !== undefined && { group: node.group }),
 		});
 	}
 
@@ -140,14 +378,30 @@ export function buildGraph(file: GraphGardenFile, config: GraphGardenConfig): Gr
 			...(targetColor !== undefined && { color: targetColor }),
 		});
 
-		const edgeColor = edge.type === "friend" ? config.friendEdgeColor : config.localEdgeColor;
+		const programType = edgeProgramType(edge);
+		const edgeColor = programType === "friend" ? config.friendEdgeColor : config.localEdgeColor;
 		graph.mergeDirectedEdge(absoluteSource, absoluteTarget, {
-			type: edge.type,
+			type: programType,
+			kind: edge.type,
 			color: edgeColor,
 			size: config.edgeSize,
+			...(edge.context !== undefined && { context: edge.context }),
 		});
 	}
 
+	// Label not-yet-fetched friend sites with their declared name
+	for (const friend of file.friends ?? []) {
+		if (typeof friend === "string" || friend.name === undefined) continue;
+		try {
+			const url = new URL(friend.url).href;
+			if (graph.hasNode(url) && graph.getNodeAttribute(url, "label") === undefined) {
+				graph.mergeNodeAttributes(url, { label: friend.name });
+			}
+		} catch {
+			// Invalid friend URLs are reported by fetchFriendGraphs
+		}
+	}
+
 	return graph;
 }
 
@@ -176,27 +430,33 @@ export function assignLayout(graph: Graph, iterations: number): void {
 export async function fetchFriendGraphs(
 	graph: Graph,
 	config: GraphGardenConfig,
-	friends: string[] | undefined = [],
+	friends: (string | GraphGardenFriend)[] | undefined = [],
 ): Promise<Graph> {
-	const origins = new Set<string>();
+	// Friend file location by origin, so each site is fetched once
+	const locations = new Map<string, string>();
 	for (const friend of friends) {
 		try {
-			origins.add(new URL(friend).origin);
+			const origin = new URL(friendUrl(friend)).origin;
+			const location =
+				typeof friend !== "string" && friend.public_file !== undefined
+					? new URL(friend.public_file).href
+					: `${origin}${WELL_KNOWN_PATH}`;
+			if (!locations.has(origin)) locations.set(origin, location);
 		} catch {
-			console.warn(`fetchFriendGraphs: invalid friend URL: ${friend}`);
+			console.warn(`fetchFriendGraphs: invalid friend URL: ${friendUrl(friend)}`);
 		}
 	}
 
 	const results = await Promise.allSettled(
-		[...origins].map(async (origin) => {
-			const response = await fetch(`${origin}${WELL_KNOWN_PATH}`);
+		[...locations].map(async ([origin, location]) => {
+			const response = await fetch(location);
 			if (!response.ok) {
 				console.warn(
 					`fetchFriendGraphs: ${origin} responded ${response.status} ${response.statusText}`,
 				);
 				return null;
 			}
-			const data: unknown = await response.json();
+			const data = await fetchShards(expandCompactFile(await response.json()), location);
 			if (!isGraphGardenFile(data)) {
 				console.warn(`fetchFriendGraphs: ${origin} returned an invalid GraphGarden file`);
 				return null;
@@ -228,13 +488,15 @@ export async function fetchFriendGraphs(
 				const absoluteSource = new URL(edge.source, friendFile.base_url).href;
 				const absoluteTarget = new URL(edge.target, friendFile.base_url).href;
 				graph.mergeNode(absoluteSource, { size: config.nodeSize });
+				const programType = edgeProgramType(edge);
 				graph.mergeNode(absoluteTarget, {
 					size: config.nodeSize,
-					...(edge.type === "friend" && { color: config.friendNodeColor }),
+					...(programType === "friend" && { color: config.friendNodeColor }),
 				});
 
 				graph.mergeDirectedEdge(absoluteSource, absoluteTarget, {
-					type: edge.type,
+					type: programType,
+					kind: edge.type,
 					color: config.friendEdgeColor,
 					size: config.edgeSize,
 				});
@@ -282,23 +544,30 @@ export class GraphGarden extends HTMLElement {
 		shadow.appendChild(this.container);
 
 		try {
-			const response = await fetch(WELL_KNOWN_PATH);
+			// A merged graph from `graphgarden merge`, or the site's own file
+			const location = this.getAttribute("src") ?? WELL_KNOWN_PATH;
+			const response = await fetch(location);
 			if (!response.ok) {
 				console.error(
-					`<graph-garden> failed to fetch ${WELL_KNOWN_PATH}: ${response.status} ${response.statusText}`,
+					`<graph-garden> failed to fetch ${location}: ${response.status} ${response.statusText}`,
 				);
 				return;
 			}
 
-			const data: unknown = await response.json();
-			if (!isGraphGardenFile(data)) {
-				console.error("<graph-garden> fetched file does not match the GraphGarden protocol shape");
-				return;
-			}
-
+			const json: unknown = await response.json();
 			const config = this.resolveConfig();
-			this.graph = buildGraph(data, config);
-			await fetchFriendGraphs(this.graph, config, data.friends);
+			if (isMergedGraph(json)) {
+				this.graph = buildMergedGraph(json, config);
+			} else {
+				const data = await fetchShards(expandCompactFile(json), location);
+				if (!isGraphGardenFile(data)) {
+					console.error("<graph-garden> fetched file does not match the GraphGarden protocol shape");
+					return;
+				}
+
+				this.graph = buildGraph(data, config);
+				await fetchFriendGraphs(this.graph, config, data.friends);
+			}
 			assignLayout(this.graph, config.iterations);
 			this.initRenderer(config);
 		} catch (error) {